@@ -20,11 +20,13 @@ pub struct MessageBus {
 
 impl MessageBus {
     pub fn new(
-        message_sender: SyncSender<WorkerMessage>,
+        message_senders: Vec<SyncSender<WorkerMessage>>,
+        routes: Vec<usize>,
+        capacity: usize,
         reply_receiver: Receiver<WorkerReply>,
     ) -> MessageBus {
         MessageBus {
-            message_sender: MessageSender::new(message_sender),
+            message_sender: MessageSender::new(message_senders, routes, capacity),
             reply_receiver,
         }
     }
@@ -32,9 +34,10 @@ impl MessageBus {
     pub fn send_suite_skip(
         &mut self,
         suite_index: usize,
+        suite: &TestSuite,
         clause: &QueryClause,
     ) -> ApplicationResult<()> {
-        self.message_sender.send_suite_skip(suite_index, clause)
+        self.message_sender.send_suite_skip(suite_index, suite, clause)
     }
 
     pub fn send_suite(&mut self, suite_index: usize, suite: &TestSuite) -> ApplicationResult<()> {
@@ -47,9 +50,11 @@ impl MessageBus {
     {
         if self.message_sender.has_messages() {
             for reply in &self.reply_receiver {
+                let suite_index = reply.suite_index();
+
                 callback(&mut self.message_sender, reply)?;
 
-                self.message_sender.dec_messages();
+                self.message_sender.replied(suite_index)?;
 
                 if !self.message_sender.has_messages() {
                     break;
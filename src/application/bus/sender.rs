@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::sync::mpsc::SyncSender;
 
 use application::ApplicationError;
@@ -7,16 +8,33 @@ use dto::QueryClause;
 use dto::TestCase;
 use dto::TestSuite;
 
+/// Sends messages to worker group of suite target, `routes` contains target index of
+/// every suite. At most `capacity` messages are dispatched to every target at once, the
+/// rest are kept pending until workers reply, so pending messages can be cancelled.
 #[derive(Debug)]
 pub struct MessageSender {
-    sender: SyncSender<WorkerMessage>,
+    senders: Vec<SyncSender<WorkerMessage>>,
+    routes: Vec<usize>,
+    capacity: usize,
+    n_dispatched: Vec<usize>,
+    pending: Vec<VecDeque<WorkerMessage>>,
     n_messages: usize,
 }
 
 impl MessageSender {
-    pub fn new(sender: SyncSender<WorkerMessage>) -> MessageSender {
+    pub fn new(
+        senders: Vec<SyncSender<WorkerMessage>>,
+        routes: Vec<usize>,
+        capacity: usize,
+    ) -> MessageSender {
+        let n_targets = senders.len();
+
         MessageSender {
-            sender: sender,
+            senders,
+            routes,
+            capacity,
+            n_dispatched: vec![0; n_targets],
+            pending: (0..n_targets).map(|_| VecDeque::new()).collect(),
             n_messages: 0,
         }
     }
@@ -24,22 +42,20 @@ impl MessageSender {
     pub fn send_suite_skip(
         &mut self,
         suite_index: usize,
+        suite: &TestSuite,
         clause: &QueryClause,
     ) -> ApplicationResult<()> {
-        let message = WorkerMessage::suite_skip(suite_index, clause);
+        let message = WorkerMessage::suite_skip(suite_index, suite, clause);
 
-        self.inc_messages();
-        self.sender
-            .send(message)
-            .map_err(ApplicationError::send_message_error)
+        self.send(suite_index, message)
     }
 
     pub fn send_suite(&mut self, suite_index: usize, suite: &TestSuite) -> ApplicationResult<()> {
         for (case_index, case) in suite.cases().iter().enumerate() {
             if let Some(skip) = case.skip() {
-                self.send_case_skip(suite_index, case_index, skip)?;
+                self.send_case_skip(suite_index, case_index, suite, case, skip)?;
             } else {
-                self.send_case_run(suite_index, case_index, case)?;
+                self.send_case_run(suite_index, case_index, suite, case)?;
             }
         }
 
@@ -50,36 +66,72 @@ impl MessageSender {
         &mut self,
         suite_index: usize,
         case_index: usize,
+        suite: &TestSuite,
+        case: &TestCase,
         clause: &QueryClause,
     ) -> ApplicationResult<()> {
-        let message = WorkerMessage::case_skip(suite_index, case_index, clause);
+        let message = WorkerMessage::case_skip(suite_index, case_index, suite, case, clause);
 
-        self.inc_messages();
-        self.sender
-            .send(message)
-            .map_err(ApplicationError::send_message_error)
+        self.send(suite_index, message)
     }
 
     pub fn send_case_run(
         &mut self,
         suite_index: usize,
         case_index: usize,
+        suite: &TestSuite,
         case: &TestCase,
     ) -> ApplicationResult<()> {
-        let message = WorkerMessage::case_run(suite_index, case_index, case);
+        let message = WorkerMessage::case_run(suite_index, case_index, suite, case);
 
-        self.inc_messages();
-        self.sender
-            .send(message)
-            .map_err(ApplicationError::send_message_error)
+        self.send(suite_index, message)
     }
 
-    fn inc_messages(&mut self) {
+    fn send(&mut self, suite_index: usize, message: WorkerMessage) -> ApplicationResult<()> {
+        let target = self.routes[suite_index];
+
         self.n_messages += 1;
+        self.pending[target].push_back(message);
+
+        self.dispatch(target)
+    }
+
+    fn dispatch(&mut self, target: usize) -> ApplicationResult<()> {
+        while self.n_dispatched[target] < self.capacity {
+            let message = match self.pending[target].pop_front() {
+                Some(message) => message,
+                None => break,
+            };
+
+            self.n_dispatched[target] += 1;
+            self.senders[target]
+                .send(message)
+                .map_err(ApplicationError::send_message_error)?;
+        }
+
+        Ok(())
     }
 
-    pub fn dec_messages(&mut self) {
+    /// Marks message of the suite as replied and dispatches pending messages.
+    pub fn replied(&mut self, suite_index: usize) -> ApplicationResult<()> {
+        let target = self.routes[suite_index];
+
         self.n_messages -= 1;
+        self.n_dispatched[target] -= 1;
+
+        self.dispatch(target)
+    }
+
+    /// Removes all messages which are not dispatched to workers yet.
+    pub fn cancel_pending(&mut self) -> Vec<WorkerMessage> {
+        let messages: Vec<WorkerMessage> = self.pending
+            .iter_mut()
+            .flat_map(|pending| pending.drain(..))
+            .collect();
+
+        self.n_messages -= messages.len();
+
+        messages
     }
 
     pub fn has_messages(&self) -> bool {
@@ -8,22 +8,35 @@ use std::sync::mpsc::SendError;
 
 use serde_yaml::Error as YamlError;
 
+use config::ConfigurationError;
+
 use super::worker::WorkerError;
 
 pub type ApplicationResult<T> = Result<T, ApplicationError>;
 
 #[derive(Debug)]
 pub enum ApplicationError {
+    ConfigurationError { message: String },
     SuiteIoError { message: String },
     SuiteYamlError { message: String },
     SuiteIsDirectory { path: PathBuf },
+    SuiteVariableError { suite: String, message: String },
     DirectoryIoError { message: String },
     NoSuitesFound,
+    FilterError { message: String },
+    TargetError { message: String },
+    ReportIoError { message: String },
     SendMessageError,
     WorkerError { message: String },
 }
 
 impl ApplicationError {
+    pub fn configuration_error(error: ConfigurationError) -> ApplicationError {
+        ApplicationError::ConfigurationError {
+            message: format!("{}", error),
+        }
+    }
+
     pub fn suite_io_error(error: IoError) -> ApplicationError {
         ApplicationError::SuiteIoError {
             message: format!("{}", error),
@@ -43,6 +56,13 @@ impl ApplicationError {
         ApplicationError::SuiteIsDirectory { path: path.into() }
     }
 
+    pub fn suite_variable_error(suite: &str, message: String) -> ApplicationError {
+        ApplicationError::SuiteVariableError {
+            suite: suite.into(),
+            message,
+        }
+    }
+
     pub fn directory_io_error(error: IoError) -> ApplicationError {
         ApplicationError::DirectoryIoError {
             message: format!("{}", error),
@@ -53,6 +73,20 @@ impl ApplicationError {
         ApplicationError::NoSuitesFound
     }
 
+    pub fn filter_error(message: String) -> ApplicationError {
+        ApplicationError::FilterError { message }
+    }
+
+    pub fn target_error(message: String) -> ApplicationError {
+        ApplicationError::TargetError { message }
+    }
+
+    pub fn report_io_error(error: IoError) -> ApplicationError {
+        ApplicationError::ReportIoError {
+            message: format!("{}", error),
+        }
+    }
+
     pub fn send_message_error<T>(_: SendError<T>) -> ApplicationError {
         ApplicationError::SendMessageError
     }
@@ -67,6 +101,9 @@ impl ApplicationError {
 impl Display for ApplicationError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match *self {
+            ApplicationError::ConfigurationError { ref message } => {
+                write!(f, "Configuration error - {}", message)
+            }
             ApplicationError::SuiteIoError { ref message } => write!(f, "IO error - {}", message),
             ApplicationError::SuiteYamlError { ref message } => {
                 write!(f, "YAML error - {}", message)
@@ -74,10 +111,23 @@ impl Display for ApplicationError {
             ApplicationError::SuiteIsDirectory { ref path } => {
                 write!(f, "Is directory - {}", path.display())
             }
+            ApplicationError::SuiteVariableError {
+                ref suite,
+                ref message,
+            } => write!(f, "Variable error - suite {}, {}", suite, message),
             ApplicationError::DirectoryIoError { ref message } => {
                 write!(f, "IO error - {}", message)
             }
             ApplicationError::NoSuitesFound => write!(f, "No suites found"),
+            ApplicationError::FilterError { ref message } => {
+                write!(f, "Filter error - {}", message)
+            }
+            ApplicationError::TargetError { ref message } => {
+                write!(f, "Target error - {}", message)
+            }
+            ApplicationError::ReportIoError { ref message } => {
+                write!(f, "Report IO error - {}", message)
+            }
             ApplicationError::SendMessageError => write!(f, "Send error, channel already closed"),
             ApplicationError::WorkerError { ref message } => {
                 write!(f, "Worker error - {}", message)
@@ -89,11 +139,16 @@ impl Display for ApplicationError {
 impl Error for ApplicationError {
     fn description(&self) -> &str {
         match *self {
+            ApplicationError::ConfigurationError { .. } => "Configuration error",
             ApplicationError::SuiteIoError { .. } => "Suite IO error",
             ApplicationError::SuiteYamlError { .. } => "Suite YAML error",
             ApplicationError::SuiteIsDirectory { .. } => "Suite is directory",
+            ApplicationError::SuiteVariableError { .. } => "Suite variable error",
             ApplicationError::DirectoryIoError { .. } => "Directory IO error",
             ApplicationError::NoSuitesFound => "No suites found",
+            ApplicationError::FilterError { .. } => "Filter error",
+            ApplicationError::TargetError { .. } => "Target error",
+            ApplicationError::ReportIoError { .. } => "Report IO error",
             ApplicationError::SendMessageError => "Send message error",
             ApplicationError::WorkerError { .. } => "Worker error",
         }
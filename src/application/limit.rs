@@ -0,0 +1,27 @@
+/// Counts failures of the test run, replies are counted by application thread only, so
+/// no more messages are dispatched once the limit is reached.
+#[derive(Debug)]
+pub struct FailureLimit {
+    max_failures: Option<usize>,
+    n_failures: usize,
+}
+
+impl FailureLimit {
+    pub fn new(max_failures: Option<usize>) -> FailureLimit {
+        FailureLimit {
+            max_failures,
+            n_failures: 0,
+        }
+    }
+
+    pub fn inc_failed(&mut self) {
+        self.n_failures += 1;
+    }
+
+    pub fn is_reached(&self) -> bool {
+        match self.max_failures {
+            Some(max_failures) => self.n_failures >= max_failures,
+            None => false,
+        }
+    }
+}
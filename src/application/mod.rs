@@ -1,15 +1,22 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::sync_channel;
 use std::sync::mpsc::SyncSender;
 use std::sync::Mutex;
 use std::thread::JoinHandle;
+use std::time::Duration;
+
+use postgres::params::ConnectParams;
+use postgres::params::Host;
 
 use config::Configuration;
+use config::Target;
 use dto::TestSuite;
 
 mod bus;
 mod error;
+mod limit;
 mod output;
 mod read;
 mod status;
@@ -23,7 +30,10 @@ pub use self::status::ApplicationStatus;
 
 use self::bus::MessageBus;
 use self::bus::MessageSender;
+use self::limit::FailureLimit;
+use self::worker::Peer;
 use self::worker::QueryResult;
+use self::worker::TlsConfig;
 use self::worker::Worker;
 use self::worker::WorkerMessage;
 use self::worker::WorkerReply;
@@ -33,56 +43,124 @@ pub struct Application<'a> {
     config: &'a Configuration,
     output: Box<Output>,
     suites: Vec<TestSuite>,
+    routes: Vec<usize>,
     status: ApplicationStatus,
+    limit: FailureLimit,
+    n_filtered: Vec<usize>,
 }
 
 impl<'a> Application<'a> {
     pub fn new(config: &'a Configuration) -> ApplicationResult<Application<'a>> {
+        let mut reader = SuiteReader::new(config)?;
+        let targets = config.targets();
+        let suites = reader.read()?;
+
+        check_compare_targets(targets, &suites)?;
+
+        let (suites, routes, n_filtered) = expand_targets(targets, suites);
+        let n_filtered = n_filtered
+            .into_iter()
+            .map(|n_filtered| n_filtered + reader.n_filtered())
+            .collect();
+
         Ok(Application {
             config,
             output: output::create_output(config),
-            suites: SuiteReader::new(config).read()?,
+            suites,
+            routes,
             status: ApplicationStatus::Success,
+            limit: FailureLimit::new(config.max_failures()),
+            n_filtered,
         })
     }
 
     pub fn run(mut self) -> ApplicationResult<ApplicationStatus> {
         let n_cases = self.get_n_cases();
-        let (message_sender, message_receiver) = sync_channel(n_cases);
         let (reply_sender, reply_receiver) = sync_channel(n_cases);
-        let workers = self.spawn_workers(message_receiver, reply_sender)?;
-        let mut bus = MessageBus::new(message_sender, reply_receiver);
+        let mut message_senders = Vec::with_capacity(self.config.targets().len());
+        let mut workers = Vec::new();
+        let mut peers = Vec::new();
+
+        for target in self.config.targets() {
+            if let Some(name) = target.name() {
+                peers.push(Peer::new(name, connect_params(target), tls_config(target)?));
+            }
+        }
+
+        for target in self.config.targets() {
+            let (message_sender, message_receiver) = sync_channel(n_cases);
+            let reply_sender = reply_sender.clone();
+
+            workers.extend(self.spawn_workers(target, &peers, message_receiver, reply_sender)?);
+            message_senders.push(message_sender);
+        }
 
-        self.output.header();
+        let mut bus = MessageBus::new(
+            message_senders,
+            self.routes.clone(),
+            self.config.n_workers(),
+            reply_receiver,
+        );
+
+        for (target, &n_filtered) in self.config.targets().iter().zip(&self.n_filtered) {
+            self.output.cases_filtered(target.name(), n_filtered);
+        }
+
+        self.output.header(n_cases);
         self.send_start_suites(&mut bus)?;
 
-        bus.message_loop(|sender, reply| match reply {
+        bus.message_loop(|sender, reply| {
+            if reply.is_failed() {
+                self.limit.inc_failed();
+            }
+
+            self.on_reply(sender, reply)?;
+
+            if self.limit.is_reached() {
+                for message in sender.cancel_pending() {
+                    self.on_reply(sender, message.not_run())?;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        self.output.footer()?;
+        self.join_workers(workers);
+
+        Ok(self.status)
+    }
+
+    fn on_reply(&mut self, sender: &mut MessageSender, reply: WorkerReply) -> ApplicationResult<()> {
+        match reply {
             WorkerReply::SuiteSkip {
                 suite_index,
                 result,
-            } => self.on_suite_skip(sender, suite_index, result),
+                duration,
+            } => self.on_suite_skip(sender, suite_index, result, duration),
             WorkerReply::CaseSkip {
                 suite_index,
                 case_index,
                 result,
-            } => self.on_case_skip(sender, suite_index, case_index, result),
+                duration,
+            } => self.on_case_skip(sender, suite_index, case_index, result, duration),
             WorkerReply::CaseRun {
                 suite_index,
                 case_index,
                 result,
-            } => self.on_case_run(suite_index, case_index, result),
-        })?;
-
-        self.output.footer();
-        self.join_workers(workers);
-
-        Ok(self.status)
+                duration,
+            } => self.on_case_run(suite_index, case_index, result, duration),
+            WorkerReply::NotRun {
+                suite_index,
+                case_index,
+            } => self.on_not_run(suite_index, case_index),
+        }
     }
 
     fn send_start_suites(&mut self, bus: &mut MessageBus) -> ApplicationResult<()> {
         for (suite_index, suite) in self.suites.iter().enumerate() {
             if let Some(skip) = suite.skip() {
-                bus.send_suite_skip(suite_index, skip)?;
+                bus.send_suite_skip(suite_index, suite, skip)?;
             } else {
                 self.output.suite_started(suite);
 
@@ -97,32 +175,30 @@ impl<'a> Application<'a> {
         for worker in workers {
             match worker.join() {
                 Ok(_) => {}
-                Err(_) => println!("Failed to join worker thread"),
+                Err(_) => eprintln!("Failed to join worker thread"),
             }
         }
     }
 
     fn spawn_workers(
         &self,
+        target: &Target,
+        peers: &[Peer],
         message_receiver: Receiver<WorkerMessage>,
         reply_sender: SyncSender<WorkerReply>,
     ) -> ApplicationResult<Vec<JoinHandle<()>>> {
         let n_workers = self.config.n_workers();
         let message_receiver = Arc::new(Mutex::new(message_receiver));
         let mut workers = Vec::with_capacity(n_workers);
+        let tls = tls_config(target)?;
 
         for _ in 0..n_workers {
             let message_receiver = message_receiver.clone();
             let reply_sender = reply_sender.clone();
-            let worker_handler = Worker::new(
-                message_receiver,
-                reply_sender,
-                self.config.hostname(),
-                self.config.port(),
-                self.config.database(),
-                self.config.username(),
-                self.config.password(),
-            ).start()
+            let worker_handler = Worker::new(message_receiver, reply_sender, connect_params(target))
+                .with_tls(tls.clone())
+                .with_peers(peers.to_vec())
+                .start()
                 .map_err(ApplicationError::worker_error)?;
 
             workers.push(worker_handler);
@@ -136,15 +212,24 @@ impl<'a> Application<'a> {
         suite_index: usize,
         case_index: usize,
         result: QueryResult,
+        duration: Duration,
     ) -> ApplicationResult<()> {
         let suite = &self.suites[suite_index];
         let case = &suite.cases()[case_index];
 
         match result {
-            QueryResult::Success => self.output.case_passed(suite, case),
+            QueryResult::Success => self.output.case_passed(suite, case, duration),
             QueryResult::Fail { ref message } | QueryResult::Error { ref message } => {
                 self.status = ApplicationStatus::Fail;
-                self.output.case_failed(suite, case, message);
+                self.output.case_failed(suite, case, message, duration);
+            }
+            QueryResult::SetupError { ref message } => {
+                self.status = ApplicationStatus::Fail;
+                self.output.case_errored(suite, case, message, duration);
+            }
+            QueryResult::Timeout { ref message } => {
+                self.status = ApplicationStatus::Fail;
+                self.output.case_timed_out(suite, case, message, duration);
             }
         }
 
@@ -157,16 +242,19 @@ impl<'a> Application<'a> {
         suite_index: usize,
         case_index: usize,
         result: QueryResult,
+        duration: Duration,
     ) -> ApplicationResult<()> {
         let suite = &self.suites[suite_index];
         let case = &suite.cases()[case_index];
 
         match result {
-            QueryResult::Success => self.output.case_skipped(suite, case),
-            QueryResult::Fail { .. } => sender.send_case_run(suite_index, case_index, case)?,
-            QueryResult::Error { ref message } => {
+            QueryResult::Success => self.output.case_skipped(suite, case, duration),
+            QueryResult::Fail { .. } => sender.send_case_run(suite_index, case_index, suite, case)?,
+            QueryResult::Error { ref message }
+            | QueryResult::SetupError { ref message }
+            | QueryResult::Timeout { ref message } => {
                 self.status = ApplicationStatus::Fail;
-                self.output.case_failed(suite, case, message);
+                self.output.case_failed(suite, case, message, duration);
             }
         }
 
@@ -178,16 +266,19 @@ impl<'a> Application<'a> {
         sender: &mut MessageSender,
         suite_index: usize,
         result: QueryResult,
+        duration: Duration,
     ) -> ApplicationResult<()> {
         let suite = &self.suites[suite_index];
 
         match result {
-            QueryResult::Success => self.output.suite_skipped(suite),
+            QueryResult::Success => self.output.suite_skipped(suite, duration),
             QueryResult::Fail { .. } => {
                 self.output.suite_started(suite);
                 sender.send_suite(suite_index, suite)?;
             }
-            QueryResult::Error { ref message } => {
+            QueryResult::Error { ref message }
+            | QueryResult::SetupError { ref message }
+            | QueryResult::Timeout { ref message } => {
                 self.status = ApplicationStatus::Fail;
                 self.output.suite_failed(suite, message)
             }
@@ -196,7 +287,126 @@ impl<'a> Application<'a> {
         Ok(())
     }
 
+    fn on_not_run(&mut self, suite_index: usize, case_index: Option<usize>) -> ApplicationResult<()> {
+        let suite = &self.suites[suite_index];
+
+        match case_index {
+            Some(case_index) => self.output.case_not_run(suite, &suite.cases()[case_index]),
+            None => {
+                self.output.suite_started(suite);
+
+                for case in suite.cases() {
+                    self.output.case_not_run(suite, case);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn get_n_cases(&self) -> usize {
         self.suites.iter().map(|s| s.cases().len()).sum()
     }
 }
+
+fn check_compare_targets(targets: &[Target], suites: &[TestSuite]) -> ApplicationResult<()> {
+    for suite in suites {
+        for case in suite.cases() {
+            if let Some(compare) = case.compare() {
+                if !targets.iter().any(|target| target.name() == Some(compare.with())) {
+                    return Err(ApplicationError::target_error(format!(
+                        "case {}::{} compares with unknown target '{}'",
+                        suite.name(),
+                        case.name(),
+                        compare.with()
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Clones suites for every named target, returns suites with target index of each suite
+/// and number of filtered out cases of every target. Comparison cases are not run on target
+/// they compare with and counted as filtered out.
+fn expand_targets(
+    targets: &[Target],
+    suites: Vec<TestSuite>,
+) -> (Vec<TestSuite>, Vec<usize>, Vec<usize>) {
+    if targets.len() == 1 && targets[0].name().is_none() {
+        let routes = vec![0; suites.len()];
+
+        return (suites, routes, vec![0]);
+    }
+
+    let mut result = Vec::with_capacity(targets.len() * suites.len());
+    let mut routes = Vec::with_capacity(targets.len() * suites.len());
+    let mut n_filtered = vec![0; targets.len()];
+
+    for (target_index, target) in targets.iter().enumerate() {
+        for suite in &suites {
+            let mut suite = suite.clone();
+
+            if let Some(name) = target.name() {
+                let cases = suite
+                    .cases()
+                    .iter()
+                    .filter(|case| case.compare().map(|compare| compare.with()) != Some(name))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                n_filtered[target_index] += suite.cases().len() - cases.len();
+
+                if cases.is_empty() {
+                    continue;
+                }
+
+                suite.set_target(name);
+                suite.set_cases(cases);
+            }
+
+            result.push(suite);
+            routes.push(target_index);
+        }
+    }
+
+    (result, routes, n_filtered)
+}
+
+fn connect_params(target: &Target) -> ConnectParams {
+    let mut builder = ConnectParams::builder();
+
+    builder
+        .port(target.port())
+        .user(
+            target.username(),
+            target.password().map(|value| value.as_str()),
+        )
+        .database(target.database())
+        .connect_timeout(target.connect_timeout());
+
+    for (name, value) in target.options() {
+        builder.option(name, value);
+    }
+
+    if target.is_unix_socket() {
+        builder.build(Host::Unix(PathBuf::from(target.hostname())))
+    } else {
+        builder.build(Host::Tcp(target.hostname().clone()))
+    }
+}
+
+fn tls_config(target: &Target) -> ApplicationResult<TlsConfig> {
+    if target.is_unix_socket() {
+        return Ok(TlsConfig::Disabled);
+    }
+
+    TlsConfig::new(
+        target.ssl_mode(),
+        target.ssl_root_cert(),
+        target.ssl_cert(),
+        target.ssl_key(),
+    ).map_err(ApplicationError::worker_error)
+}
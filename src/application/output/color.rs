@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use term::color::Color;
 use term::color::GREEN;
 use term::color::RED;
@@ -7,66 +9,106 @@ use term::Result as TermResult;
 use term::StdoutTerminal;
 use term;
 
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::case_name;
+use super::format_duration;
+use super::suite_name;
 use super::Formatter;
+use super::TestCounters;
 
 #[derive(Debug)]
 pub struct ColorFormatter {}
 
 impl Formatter for ColorFormatter {
-    fn header(&self) {
+    fn header(&self, _: usize) {
         println!();
         println!("running tests...");
         println!();
     }
 
-    fn footer(&self, passed: usize, skipped: usize, failed: usize) {
+    fn footer(&self, counters: &TestCounters, targets: &[(String, TestCounters)]) {
         println!();
-        print!("test result: ");
 
-        if passed == 0 && failed == 0 {
-            print_with_color(YELLOW, "skipped");
-        } else if failed > 0 {
-            print_with_color(RED, "failed");
-        } else {
-            print_with_color(GREEN, "passed");
+        if !targets.is_empty() {
+            for (target, counters) in targets {
+                print!("target {}: ", target);
+                print_counters(counters);
+            }
+
+            println!();
         }
 
-        println!(
-            ". {} passed; {} failed; {} skipped",
-            passed, failed, skipped
-        );
+        print!("test result: ");
+        print_counters(counters);
         println!();
     }
 
-    fn suite_started(&mut self, suite_name: &str) {
-        println!("suite {} .. started", suite_name);
+    fn suite_started(&mut self, suite: &TestSuite) {
+        println!("suite {} .. started", suite_name(suite));
     }
 
-    fn suite_failed(&mut self, suite_name: &str, message: &str) {
-        print!("suite {} .. ", suite_name);
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        print!("suite {} .. ", suite_name(suite));
         println_with_color(RED, "error");
         println!("  - {}", message);
     }
 
-    fn suite_skipped(&mut self, suite_name: &str) {
-        print!("suite {} .. ", suite_name);
-        println_with_color(YELLOW, "skipped");
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration) {
+        print!("suite {} .. ", suite_name(suite));
+        print_with_color(YELLOW, "skipped");
+        println!(" ({})", format_duration(duration));
     }
 
-    fn case_passed(&mut self, suite_name: &str, case_name: &str) {
-        print!("test {}::{} .. ", suite_name, case_name);
-        println_with_color(GREEN, "passed");
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        print!("test {}::{} .. ", suite_name(suite), case_name(case));
+        print_with_color(GREEN, "passed");
+        println!(" ({})", format_duration(duration));
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        print!("test {}::{} .. ", suite_name(suite), case_name(case));
+        print_with_color(RED, "failed");
+        println!(" ({})", format_duration(duration));
+        println!("    - {}", message);
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        print!("test {}::{} .. ", suite_name(suite), case_name(case));
+        print_with_color(RED, "error");
+        println!(" ({})", format_duration(duration));
+        println!("    - {}", message);
     }
 
-    fn case_failed(&mut self, suite_name: &str, case_name: &str, message: &str) {
-        print!("test {}::{} .. ", suite_name, case_name);
-        println_with_color(RED, "failed");
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        print!("test {}::{} .. ", suite_name(suite), case_name(case));
+        print_with_color(RED, "timed out");
+        println!(" ({})", format_duration(duration));
         println!("    - {}", message);
     }
 
-    fn case_skipped(&mut self, suite_name: &str, case_name: &str) {
-        print!("test {}::{} .. ", suite_name, case_name);
-        println_with_color(YELLOW, "skipped");
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        print!("test {}::{} .. ", suite_name(suite), case_name(case));
+        print_with_color(YELLOW, "skipped");
+        println!(" ({})", format_duration(duration));
+    }
+
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
+        print!("test {}::{} .. ", suite_name(suite), case_name(case));
+        println_with_color(YELLOW, "not run");
     }
 }
 
@@ -76,6 +118,37 @@ impl Default for ColorFormatter {
     }
 }
 
+fn print_counters(counters: &TestCounters) {
+    let passed = counters.passed();
+    let skipped = counters.skipped();
+    let failed = counters.failed();
+    let not_run = counters.not_run();
+    let filtered = counters.filtered();
+
+    if passed == 0 && failed == 0 {
+        print_with_color(YELLOW, "skipped");
+    } else if failed > 0 {
+        print_with_color(RED, "failed");
+    } else {
+        print_with_color(GREEN, "passed");
+    }
+
+    print!(
+        ". {} passed; {} failed; {} skipped",
+        passed, failed, skipped
+    );
+
+    if not_run > 0 {
+        print!("; {} not run", not_run);
+    }
+
+    if filtered > 0 {
+        print!("; {} filtered out", filtered);
+    }
+
+    println!();
+}
+
 fn try_terminal<F>(callback: F) -> TermResult<()>
 where
     F: Fn(&mut StdoutTerminal) -> TermResult<()>,
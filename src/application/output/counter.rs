@@ -3,6 +3,8 @@ pub struct TestCounters {
     passed: usize,
     skipped: usize,
     failed: usize,
+    not_run: usize,
+    filtered: usize,
 }
 
 impl TestCounters {
@@ -18,6 +20,14 @@ impl TestCounters {
         self.failed += 1;
     }
 
+    pub fn inc_not_run(&mut self) {
+        self.not_run += 1;
+    }
+
+    pub fn add_filtered(&mut self, value: usize) {
+        self.filtered += value;
+    }
+
     pub fn add_skipped(&mut self, value: usize) {
         self.skipped += value;
     }
@@ -37,6 +47,14 @@ impl TestCounters {
     pub fn failed(&self) -> usize {
         self.failed
     }
+
+    pub fn not_run(&self) -> usize {
+        self.not_run
+    }
+
+    pub fn filtered(&self) -> usize {
+        self.filtered
+    }
 }
 
 impl Default for TestCounters {
@@ -45,6 +63,8 @@ impl Default for TestCounters {
             passed: 0,
             skipped: 0,
             failed: 0,
+            not_run: 0,
+            filtered: 0,
         }
     }
 }
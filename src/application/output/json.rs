@@ -0,0 +1,367 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json;
+
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::seconds;
+use super::Formatter;
+use super::TestCounters;
+
+#[derive(Debug, Serialize)]
+struct SuiteInfo {
+    #[serde(skip_serializing_if = "Option::is_none")] target: Option<String>,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")] description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CaseInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")] description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event")]
+enum Event {
+    #[serde(rename = "suite_started")] SuiteStarted { suite: SuiteInfo },
+    #[serde(rename = "suite_failed")] SuiteFailed { suite: SuiteInfo, message: String },
+    #[serde(rename = "suite_skipped")] SuiteSkipped { suite: SuiteInfo, duration: f64 },
+    #[serde(rename = "case_passed")]
+    CasePassed {
+        suite: SuiteInfo,
+        case: CaseInfo,
+        duration: f64,
+    },
+    #[serde(rename = "case_failed")]
+    CaseFailed {
+        suite: SuiteInfo,
+        case: CaseInfo,
+        message: String,
+        duration: f64,
+    },
+    #[serde(rename = "case_errored")]
+    CaseErrored {
+        suite: SuiteInfo,
+        case: CaseInfo,
+        message: String,
+        duration: f64,
+    },
+    #[serde(rename = "case_timed_out")]
+    CaseTimedOut {
+        suite: SuiteInfo,
+        case: CaseInfo,
+        message: String,
+        duration: f64,
+    },
+    #[serde(rename = "case_skipped")]
+    CaseSkipped {
+        suite: SuiteInfo,
+        case: CaseInfo,
+        duration: f64,
+    },
+    #[serde(rename = "case_not_run")] CaseNotRun { suite: SuiteInfo, case: CaseInfo },
+    #[serde(rename = "finished")] Finished(Counters),
+}
+
+#[derive(Debug, Serialize)]
+struct Counters {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    not_run: usize,
+    filtered: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")] targets: Vec<TargetCounters>,
+}
+
+#[derive(Debug, Serialize)]
+struct TargetCounters {
+    name: String,
+    #[serde(flatten)] counters: Counters,
+}
+
+#[derive(Debug, Serialize)]
+struct Document<'a> {
+    events: &'a [Event],
+    #[serde(flatten)] counters: Counters,
+}
+
+#[derive(Debug)]
+pub struct JsonFormatter {
+    events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub struct JsonLinesFormatter {}
+
+impl Formatter for JsonFormatter {
+    fn header(&self, _: usize) {}
+
+    fn footer(&self, counters: &TestCounters, targets: &[(String, TestCounters)]) {
+        let document = Document {
+            events: &self.events,
+            counters: Counters::new(counters, targets),
+        };
+
+        println!("{}", to_json(&document));
+    }
+
+    fn suite_started(&mut self, suite: &TestSuite) {
+        self.events.push(Event::suite_started(suite));
+    }
+
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        self.events.push(Event::suite_failed(suite, message));
+    }
+
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration) {
+        self.events.push(Event::suite_skipped(suite, duration));
+    }
+
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        self.events.push(Event::case_passed(suite, case, duration));
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        self.events
+            .push(Event::case_failed(suite, case, message, duration));
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        self.events
+            .push(Event::case_errored(suite, case, message, duration));
+    }
+
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        self.events
+            .push(Event::case_timed_out(suite, case, message, duration));
+    }
+
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        self.events.push(Event::case_skipped(suite, case, duration));
+    }
+
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
+        self.events.push(Event::case_not_run(suite, case));
+    }
+}
+
+impl Default for JsonFormatter {
+    fn default() -> JsonFormatter {
+        JsonFormatter {
+            events: Vec::default(),
+        }
+    }
+}
+
+impl Formatter for JsonLinesFormatter {
+    fn header(&self, _: usize) {}
+
+    fn footer(&self, counters: &TestCounters, targets: &[(String, TestCounters)]) {
+        println!(
+            "{}",
+            to_json(&Event::Finished(Counters::new(counters, targets)))
+        );
+    }
+
+    fn suite_started(&mut self, suite: &TestSuite) {
+        println!("{}", to_json(&Event::suite_started(suite)));
+    }
+
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        println!("{}", to_json(&Event::suite_failed(suite, message)));
+    }
+
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration) {
+        println!("{}", to_json(&Event::suite_skipped(suite, duration)));
+    }
+
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        println!("{}", to_json(&Event::case_passed(suite, case, duration)));
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        println!(
+            "{}",
+            to_json(&Event::case_failed(suite, case, message, duration))
+        );
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        println!(
+            "{}",
+            to_json(&Event::case_errored(suite, case, message, duration))
+        );
+    }
+
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        println!(
+            "{}",
+            to_json(&Event::case_timed_out(suite, case, message, duration))
+        );
+    }
+
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        println!("{}", to_json(&Event::case_skipped(suite, case, duration)));
+    }
+
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
+        println!("{}", to_json(&Event::case_not_run(suite, case)));
+    }
+}
+
+impl Default for JsonLinesFormatter {
+    fn default() -> JsonLinesFormatter {
+        JsonLinesFormatter {}
+    }
+}
+
+impl Event {
+    fn suite_started(suite: &TestSuite) -> Event {
+        Event::SuiteStarted {
+            suite: SuiteInfo::new(suite),
+        }
+    }
+
+    fn suite_failed(suite: &TestSuite, message: &str) -> Event {
+        Event::SuiteFailed {
+            suite: SuiteInfo::new(suite),
+            message: message.into(),
+        }
+    }
+
+    fn suite_skipped(suite: &TestSuite, duration: Duration) -> Event {
+        Event::SuiteSkipped {
+            suite: SuiteInfo::new(suite),
+            duration: seconds(duration),
+        }
+    }
+
+    fn case_passed(suite: &TestSuite, case: &TestCase, duration: Duration) -> Event {
+        Event::CasePassed {
+            suite: SuiteInfo::new(suite),
+            case: CaseInfo::new(case),
+            duration: seconds(duration),
+        }
+    }
+
+    fn case_failed(suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) -> Event {
+        Event::CaseFailed {
+            suite: SuiteInfo::new(suite),
+            case: CaseInfo::new(case),
+            message: message.into(),
+            duration: seconds(duration),
+        }
+    }
+
+    fn case_errored(suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) -> Event {
+        Event::CaseErrored {
+            suite: SuiteInfo::new(suite),
+            case: CaseInfo::new(case),
+            message: message.into(),
+            duration: seconds(duration),
+        }
+    }
+
+    fn case_timed_out(
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) -> Event {
+        Event::CaseTimedOut {
+            suite: SuiteInfo::new(suite),
+            case: CaseInfo::new(case),
+            message: message.into(),
+            duration: seconds(duration),
+        }
+    }
+
+    fn case_skipped(suite: &TestSuite, case: &TestCase, duration: Duration) -> Event {
+        Event::CaseSkipped {
+            suite: SuiteInfo::new(suite),
+            case: CaseInfo::new(case),
+            duration: seconds(duration),
+        }
+    }
+
+    fn case_not_run(suite: &TestSuite, case: &TestCase) -> Event {
+        Event::CaseNotRun {
+            suite: SuiteInfo::new(suite),
+            case: CaseInfo::new(case),
+        }
+    }
+}
+
+impl Counters {
+    fn new(counters: &TestCounters, targets: &[(String, TestCounters)]) -> Counters {
+        Counters {
+            passed: counters.passed(),
+            failed: counters.failed(),
+            skipped: counters.skipped(),
+            not_run: counters.not_run(),
+            filtered: counters.filtered(),
+            targets: targets
+                .iter()
+                .map(|(name, counters)| TargetCounters {
+                    name: name.clone(),
+                    counters: Counters::new(counters, &[]),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl SuiteInfo {
+    fn new(suite: &TestSuite) -> SuiteInfo {
+        SuiteInfo {
+            target: suite.target().cloned(),
+            name: suite.name().clone(),
+            description: suite.description().cloned(),
+            path: suite.path().map(|path| path.display().to_string()),
+        }
+    }
+}
+
+impl CaseInfo {
+    fn new(case: &TestCase) -> CaseInfo {
+        CaseInfo {
+            name: case.name().clone(),
+            description: case.description().cloned(),
+        }
+    }
+}
+
+fn to_json<T>(value: &T) -> String
+where
+    T: Serialize,
+{
+    serde_json::to_string(value).unwrap_or_default()
+}
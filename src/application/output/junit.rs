@@ -0,0 +1,322 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::ptr;
+use std::time::Duration;
+
+use application::ApplicationError;
+use application::ApplicationResult;
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::qualified_name;
+use super::seconds;
+use super::Output;
+
+#[derive(Debug)]
+pub struct JUnitOutput {
+    path: PathBuf,
+    suites: Vec<SuiteRecord>,
+}
+
+#[derive(Debug)]
+struct SuiteRecord {
+    name: String,
+    cases: Vec<CaseRecord>,
+}
+
+#[derive(Debug)]
+struct CaseRecord {
+    name: String,
+    status: Option<CaseStatus>,
+    duration: Duration,
+}
+
+#[derive(Debug)]
+enum CaseStatus {
+    Passed,
+    Failed(String),
+    Errored(String),
+    Skipped(String),
+}
+
+impl JUnitOutput {
+    pub fn new(path: &Path) -> JUnitOutput {
+        JUnitOutput {
+            path: path.into(),
+            suites: Vec::default(),
+        }
+    }
+
+    fn suite_record(&mut self, suite: &TestSuite) -> &mut SuiteRecord {
+        let position = self.suites
+            .iter()
+            .position(|record| record.name == qualified_name(suite));
+
+        match position {
+            Some(index) => &mut self.suites[index],
+            None => {
+                self.suites.push(SuiteRecord::new(suite));
+
+                self.suites.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn set_suite_status<F>(&mut self, suite: &TestSuite, status: F)
+    where
+        F: Fn() -> CaseStatus,
+    {
+        for case in &mut self.suite_record(suite).cases {
+            case.status = Some(status());
+        }
+    }
+
+    fn set_case_status(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        status: CaseStatus,
+        duration: Duration,
+    ) {
+        let position = suite.cases().iter().position(|item| ptr::eq(item, case));
+        let record = self.suite_record(suite);
+
+        if let Some(case) = position.and_then(|index| record.cases.get_mut(index)) {
+            case.status = Some(status);
+            case.duration = duration;
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        let mut tests = 0;
+        let mut failures = 0;
+        let mut errors = 0;
+        let mut skipped = 0;
+        let mut duration = Duration::default();
+        let mut body = String::new();
+
+        for suite in &self.suites {
+            tests += suite.cases.len();
+            failures += suite.failures();
+            errors += suite.errors();
+            skipped += suite.skipped();
+            duration += suite.duration();
+            body.push_str(&suite.to_xml());
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">\n{}</testsuites>\n",
+            tests,
+            failures,
+            errors,
+            skipped,
+            format_duration(duration),
+            body
+        )
+    }
+}
+
+impl SuiteRecord {
+    fn new(suite: &TestSuite) -> SuiteRecord {
+        SuiteRecord {
+            name: qualified_name(suite),
+            cases: suite
+                .cases()
+                .iter()
+                .map(|case| CaseRecord {
+                    name: case.name().clone(),
+                    status: None,
+                    duration: Duration::default(),
+                })
+                .collect(),
+        }
+    }
+
+    fn failures(&self) -> usize {
+        self.count(|status| match *status {
+            Some(CaseStatus::Failed(..)) => true,
+            _ => false,
+        })
+    }
+
+    fn errors(&self) -> usize {
+        self.count(|status| match *status {
+            Some(CaseStatus::Errored(..)) => true,
+            _ => false,
+        })
+    }
+
+    fn skipped(&self) -> usize {
+        self.count(|status| match *status {
+            Some(CaseStatus::Skipped(..)) | None => true,
+            _ => false,
+        })
+    }
+
+    fn count<F>(&self, predicate: F) -> usize
+    where
+        F: Fn(&Option<CaseStatus>) -> bool,
+    {
+        self.cases
+            .iter()
+            .filter(|case| predicate(&case.status))
+            .count()
+    }
+
+    fn duration(&self) -> Duration {
+        self.cases
+            .iter()
+            .fold(Duration::default(), |acc, case| acc + case.duration)
+    }
+
+    fn to_xml(&self) -> String {
+        let mut result = String::new();
+
+        result.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            escape(&self.name),
+            self.cases.len(),
+            self.failures(),
+            self.errors(),
+            self.skipped(),
+            format_duration(self.duration())
+        ));
+
+        for case in &self.cases {
+            result.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape(&case.name),
+                escape(&self.name),
+                format_duration(case.duration)
+            ));
+
+            match case.status {
+                Some(CaseStatus::Passed) => result.push_str("/>\n"),
+                Some(CaseStatus::Failed(ref message)) => result.push_str(&format!(
+                    ">\n      <failure message=\"{0}\">{0}</failure>\n    </testcase>\n",
+                    escape(message)
+                )),
+                Some(CaseStatus::Errored(ref message)) => result.push_str(&format!(
+                    ">\n      <error message=\"{0}\">{0}</error>\n    </testcase>\n",
+                    escape(message)
+                )),
+                Some(CaseStatus::Skipped(ref message)) => result.push_str(&format!(
+                    ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                    escape(message)
+                )),
+                None => result.push_str(">\n      <skipped message=\"Not run\"/>\n    </testcase>\n"),
+            }
+        }
+
+        result.push_str("  </testsuite>\n");
+        result
+    }
+}
+
+impl Output for JUnitOutput {
+    fn cases_filtered(&mut self, _: Option<&String>, _: usize) {}
+
+    fn header(&self, _: usize) {}
+
+    fn footer(&self) -> ApplicationResult<()> {
+        let mut file = File::create(&self.path).map_err(ApplicationError::report_io_error)?;
+
+        file.write_all(self.to_xml().as_bytes())
+            .map_err(ApplicationError::report_io_error)
+    }
+
+    fn suite_started(&mut self, suite: &TestSuite) {
+        self.suite_record(suite);
+    }
+
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        self.set_suite_status(suite, || CaseStatus::Errored(message.into()));
+    }
+
+    fn suite_skipped(&mut self, suite: &TestSuite, _: Duration) {
+        let reason = skip_reason(suite.skip().map(|skip| skip.query()));
+
+        self.set_suite_status(suite, || CaseStatus::Skipped(reason.clone()));
+    }
+
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        self.set_case_status(suite, case, CaseStatus::Passed, duration);
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        self.set_case_status(suite, case, CaseStatus::Failed(message.into()), duration);
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        self.set_case_status(suite, case, CaseStatus::Errored(message.into()), duration);
+    }
+
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        self.set_case_status(suite, case, CaseStatus::Errored(message.into()), duration);
+    }
+
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        let reason = skip_reason(case.skip().map(|skip| skip.query()));
+
+        self.set_case_status(suite, case, CaseStatus::Skipped(reason), duration);
+    }
+
+    fn case_not_run(&mut self, _: &TestSuite, _: &TestCase) {}
+}
+
+fn skip_reason(query: Option<&String>) -> String {
+    match query {
+        Some(query) => format!("Skip condition matched - {}", query),
+        None => "Skipped".into(),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}", seconds(duration))
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            '\n' => result.push_str("&#10;"),
+            c if c.is_control() => {}
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::escape;
+
+    #[test]
+    fn escape_xml_special_characters() {
+        assert_eq!(
+            "a &lt; b &amp;&amp; c &gt; &quot;d&quot;&#10;&apos;e&apos;",
+            escape("a < b && c > \"d\"\n'e'\u{1}")
+        );
+    }
+}
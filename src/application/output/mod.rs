@@ -1,38 +1,78 @@
 use std::fmt::Debug;
+use std::time::Duration;
 
 mod color;
 mod counter;
+mod json;
+mod junit;
 mod plain;
+mod tap;
 
+use application::ApplicationResult;
 use config::Configuration;
+use config::OutputFormat;
 use dto::TestCase;
 use dto::TestSuite;
 
 pub use self::color::ColorFormatter;
+pub use self::json::JsonFormatter;
+pub use self::json::JsonLinesFormatter;
+pub use self::junit::JUnitOutput;
 pub use self::plain::PlainFormatter;
-
-use self::counter::TestCounters;
+pub use self::counter::TestCounters;
+pub use self::tap::TapFormatter;
 
 pub trait Formatter: Debug {
-    fn header(&self);
-    fn footer(&self, passed: usize, skipped: usize, failed: usize);
-    fn suite_started(&mut self, suite_name: &str);
-    fn suite_failed(&mut self, suite_name: &str, message: &str);
-    fn suite_skipped(&mut self, suite_name: &str);
-    fn case_passed(&mut self, suite_name: &str, case_name: &str);
-    fn case_failed(&mut self, suite_name: &str, case_name: &str, message: &str);
-    fn case_skipped(&mut self, suite_name: &str, case_name: &str);
+    fn header(&self, n_cases: usize);
+    fn footer(&self, counters: &TestCounters, targets: &[(String, TestCounters)]);
+    fn suite_started(&mut self, suite: &TestSuite);
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str);
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration);
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration);
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration);
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    );
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    );
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration);
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase);
 }
 
 pub trait Output: Debug {
-    fn header(&self);
-    fn footer(&self);
+    fn cases_filtered(&mut self, target: Option<&String>, n_cases: usize);
+    fn header(&self, n_cases: usize);
+    fn footer(&self) -> ApplicationResult<()>;
     fn suite_started(&mut self, suite: &TestSuite);
     fn suite_failed(&mut self, suite: &TestSuite, message: &str);
-    fn suite_skipped(&mut self, suite: &TestSuite);
-    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase);
-    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str);
-    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase);
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration);
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration);
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration);
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    );
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    );
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration);
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase);
 }
 
 #[derive(Debug, PartialEq, PartialOrd)]
@@ -65,16 +105,39 @@ where
 {
     formatter: F,
     counters: TestCounters,
+    targets: Vec<(String, TestCounters)>,
     verbosity: Verbosity,
 }
 
+#[derive(Debug)]
+struct CompositeOutput {
+    outputs: Vec<Box<Output>>,
+}
+
 pub fn create_output(config: &Configuration) -> Box<Output> {
     let verbosity = config.verbosity().into();
+    let targets: Vec<String> = config
+        .targets()
+        .iter()
+        .filter_map(|target| target.name().cloned())
+        .collect();
+    let console: Box<Output> = match config.format() {
+        OutputFormat::Json => Box::new(OutputImpl::<JsonFormatter>::new(Verbosity::All, targets)),
+        OutputFormat::JsonLines => {
+            Box::new(OutputImpl::<JsonLinesFormatter>::new(Verbosity::All, targets))
+        }
+        OutputFormat::Tap => Box::new(OutputImpl::<TapFormatter>::new(Verbosity::All, targets)),
+        OutputFormat::Text if config.text_mode() => {
+            Box::new(OutputImpl::<PlainFormatter>::new(verbosity, targets))
+        }
+        OutputFormat::Text => Box::new(OutputImpl::<ColorFormatter>::new(verbosity, targets)),
+    };
 
-    if config.text_mode() {
-        Box::new(OutputImpl::<PlainFormatter>::new(verbosity))
-    } else {
-        Box::new(OutputImpl::<ColorFormatter>::new(verbosity))
+    match config.junit() {
+        Some(path) => Box::new(CompositeOutput {
+            outputs: vec![console, Box::new(JUnitOutput::new(path))],
+        }),
+        None => console,
     }
 }
 
@@ -82,93 +145,262 @@ impl<F> OutputImpl<F>
 where
     F: Formatter + Default,
 {
-    fn new(verbosity: Verbosity) -> OutputImpl<F> {
+    fn new(verbosity: Verbosity, targets: Vec<String>) -> OutputImpl<F> {
         OutputImpl {
             formatter: F::default(),
             counters: TestCounters::default(),
+            targets: targets
+                .into_iter()
+                .map(|target| (target, TestCounters::default()))
+                .collect(),
             verbosity,
         }
     }
 }
 
+impl<F> OutputImpl<F>
+where
+    F: Formatter,
+{
+    fn count<C>(&mut self, suite: &TestSuite, update: C)
+    where
+        C: Fn(&mut TestCounters),
+    {
+        self.count_target(suite.target(), update);
+    }
+
+    fn count_target<C>(&mut self, target: Option<&String>, update: C)
+    where
+        C: Fn(&mut TestCounters),
+    {
+        update(&mut self.counters);
+
+        if let Some(target) = target {
+            if let Some(&mut (_, ref mut counters)) =
+                self.targets.iter_mut().find(|&&mut (ref name, _)| name == target)
+            {
+                update(counters);
+            }
+        }
+    }
+}
+
 impl<F> Output for OutputImpl<F>
 where
     F: Formatter,
 {
-    fn header(&self) {
+    fn cases_filtered(&mut self, target: Option<&String>, n_cases: usize) {
+        self.count_target(target, |counters| counters.add_filtered(n_cases));
+    }
+
+    fn header(&self, n_cases: usize) {
         if self.verbosity >= Verbosity::Failed {
-            self.formatter.header();
+            self.formatter.header(n_cases);
         }
     }
 
-    fn footer(&self) {
+    fn footer(&self) -> ApplicationResult<()> {
         if self.verbosity >= Verbosity::Results {
-            self.formatter.footer(
-                self.counters.passed(),
-                self.counters.skipped(),
-                self.counters.failed(),
-            );
+            self.formatter.footer(&self.counters, &self.targets);
         }
+
+        Ok(())
     }
 
     fn suite_started(&mut self, suite: &TestSuite) {
         if self.verbosity >= Verbosity::All {
-            let suite_name = suite.description().unwrap_or_else(|| suite.name());
-
-            self.formatter.suite_started(suite_name);
+            self.formatter.suite_started(suite);
         }
     }
 
     fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
         if self.verbosity >= Verbosity::Failed {
-            let suite_name = suite.description().unwrap_or_else(|| suite.name());
-
-            self.formatter.suite_failed(suite_name, message);
+            self.formatter.suite_failed(suite, message);
         }
 
-        self.counters.add_failed(suite.cases().len());
+        self.count(suite, |counters| counters.add_failed(suite.cases().len()));
     }
 
-    fn suite_skipped(&mut self, suite: &TestSuite) {
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration) {
         if self.verbosity >= Verbosity::PassedSkippedFailed {
-            let suite_name = suite.description().unwrap_or_else(|| suite.name());
-
-            self.formatter.suite_skipped(suite_name);
+            self.formatter.suite_skipped(suite, duration);
         }
 
-        self.counters.add_skipped(suite.cases().len());
+        self.count(suite, |counters| counters.add_skipped(suite.cases().len()));
     }
 
-    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase) {
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
         if self.verbosity >= Verbosity::PassedFailed {
-            let suite_name = suite.description().unwrap_or_else(|| suite.name());
-            let case_name = case.description().unwrap_or_else(|| case.name());
+            self.formatter.case_passed(suite, case, duration);
+        }
 
-            self.formatter.case_passed(suite_name, case_name);
+        self.count(suite, TestCounters::inc_passed);
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        if self.verbosity >= Verbosity::Failed {
+            self.formatter.case_failed(suite, case, message, duration);
+        }
+
+        self.count(suite, TestCounters::inc_failed);
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        if self.verbosity >= Verbosity::Failed {
+            self.formatter.case_errored(suite, case, message, duration);
         }
 
-        self.counters.inc_passed();
+        self.count(suite, TestCounters::inc_failed);
     }
 
-    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str) {
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
         if self.verbosity >= Verbosity::Failed {
-            let suite_name = suite.description().unwrap_or_else(|| suite.name());
-            let case_name = case.description().unwrap_or_else(|| case.name());
+            self.formatter.case_timed_out(suite, case, message, duration);
+        }
+
+        self.count(suite, TestCounters::inc_failed);
+    }
 
-            self.formatter.case_failed(suite_name, case_name, message);
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        if self.verbosity >= Verbosity::PassedSkippedFailed {
+            self.formatter.case_skipped(suite, case, duration);
         }
 
-        self.counters.inc_failed();
+        self.count(suite, TestCounters::inc_skipped);
     }
 
-    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase) {
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
         if self.verbosity >= Verbosity::PassedSkippedFailed {
-            let suite_name = suite.description().unwrap_or_else(|| suite.name());
-            let case_name = case.description().unwrap_or_else(|| case.name());
+            self.formatter.case_not_run(suite, case);
+        }
+
+        self.count(suite, TestCounters::inc_not_run);
+    }
+}
+
+impl Output for CompositeOutput {
+    fn cases_filtered(&mut self, target: Option<&String>, n_cases: usize) {
+        for output in &mut self.outputs {
+            output.cases_filtered(target, n_cases);
+        }
+    }
 
-            self.formatter.case_skipped(suite_name, case_name);
+    fn header(&self, n_cases: usize) {
+        for output in &self.outputs {
+            output.header(n_cases);
         }
+    }
+
+    fn footer(&self) -> ApplicationResult<()> {
+        for output in &self.outputs {
+            output.footer()?;
+        }
+
+        Ok(())
+    }
+
+    fn suite_started(&mut self, suite: &TestSuite) {
+        for output in &mut self.outputs {
+            output.suite_started(suite);
+        }
+    }
 
-        self.counters.inc_skipped();
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        for output in &mut self.outputs {
+            output.suite_failed(suite, message);
+        }
+    }
+
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration) {
+        for output in &mut self.outputs {
+            output.suite_skipped(suite, duration);
+        }
+    }
+
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        for output in &mut self.outputs {
+            output.case_passed(suite, case, duration);
+        }
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        for output in &mut self.outputs {
+            output.case_failed(suite, case, message, duration);
+        }
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        for output in &mut self.outputs {
+            output.case_errored(suite, case, message, duration);
+        }
     }
+
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        for output in &mut self.outputs {
+            output.case_timed_out(suite, case, message, duration);
+        }
+    }
+
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        for output in &mut self.outputs {
+            output.case_skipped(suite, case, duration);
+        }
+    }
+
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
+        for output in &mut self.outputs {
+            output.case_not_run(suite, case);
+        }
+    }
+}
+
+fn suite_name(suite: &TestSuite) -> String {
+    with_target(suite, suite.description().unwrap_or_else(|| suite.name()))
+}
+
+fn qualified_name(suite: &TestSuite) -> String {
+    with_target(suite, suite.name())
+}
+
+fn with_target(suite: &TestSuite, name: &str) -> String {
+    match suite.target() {
+        Some(target) => format!("{}::{}", target, name),
+        None => name.into(),
+    }
+}
+
+fn case_name(case: &TestCase) -> &String {
+    case.description().unwrap_or_else(|| case.name())
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", seconds(duration) * 1000.0)
 }
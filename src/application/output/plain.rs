@@ -1,58 +1,120 @@
+use std::time::Duration;
+
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::case_name;
+use super::format_duration;
+use super::suite_name;
 use super::Formatter;
+use super::TestCounters;
 
 #[derive(Debug)]
 pub struct PlainFormatter {}
 
 impl Formatter for PlainFormatter {
-    fn header(&self) {
+    fn header(&self, _: usize) {
         println!();
         println!("running tests...");
         println!();
     }
 
-    fn footer(&self, passed: usize, skipped: usize, failed: usize) {
+    fn footer(&self, counters: &TestCounters, targets: &[(String, TestCounters)]) {
         println!();
-        print!("test result: ");
 
-        if passed == 0 && failed == 0 {
-            print!("skipped");
-        } else if failed > 0 {
-            print!("failed");
-        } else {
-            print!("passed");
+        if !targets.is_empty() {
+            for (target, counters) in targets {
+                print!("target {}: ", target);
+                print_counters(counters);
+            }
+
+            println!();
         }
 
-        println!(
-            ". {} passed; {} failed; {} skipped",
-            passed, failed, skipped
-        );
+        print!("test result: ");
+        print_counters(counters);
         println!();
     }
 
-    fn suite_started(&mut self, suite_name: &str) {
-        println!("suite {} .. started", suite_name);
+    fn suite_started(&mut self, suite: &TestSuite) {
+        println!("suite {} .. started", suite_name(suite));
     }
 
-    fn suite_failed(&mut self, suite_name: &str, message: &str) {
-        println!("suite {} .. error", suite_name);
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        println!("suite {} .. error", suite_name(suite));
         println!("  - {}", message);
     }
 
-    fn suite_skipped(&mut self, suite_name: &str) {
-        println!("suite {} .. skipped", suite_name);
+    fn suite_skipped(&mut self, suite: &TestSuite, duration: Duration) {
+        println!(
+            "suite {} .. skipped ({})",
+            suite_name(suite),
+            format_duration(duration)
+        );
     }
 
-    fn case_passed(&mut self, suite_name: &str, case_name: &str) {
-        println!("test {}::{} .. passed", suite_name, case_name);
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        println!(
+            "test {}::{} .. passed ({})",
+            suite_name(suite),
+            case_name(case),
+            format_duration(duration)
+        );
     }
 
-    fn case_failed(&mut self, suite_name: &str, case_name: &str, message: &str) {
-        println!("test {}::{} .. failed", suite_name, case_name);
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        println!(
+            "test {}::{} .. failed ({})",
+            suite_name(suite),
+            case_name(case),
+            format_duration(duration)
+        );
         println!("    - {}", message);
     }
 
-    fn case_skipped(&mut self, suite_name: &str, case_name: &str) {
-        println!("test {}::{} .. skipped", suite_name, case_name);
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        println!(
+            "test {}::{} .. error ({})",
+            suite_name(suite),
+            case_name(case),
+            format_duration(duration)
+        );
+        println!("    - {}", message);
+    }
+
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        println!(
+            "test {}::{} .. timed out ({})",
+            suite_name(suite),
+            case_name(case),
+            format_duration(duration)
+        );
+        println!("    - {}", message);
+    }
+
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, duration: Duration) {
+        println!(
+            "test {}::{} .. skipped ({})",
+            suite_name(suite),
+            case_name(case),
+            format_duration(duration)
+        );
+    }
+
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
+        println!("test {}::{} .. not run", suite_name(suite), case_name(case));
     }
 }
 
@@ -61,3 +123,34 @@ impl Default for PlainFormatter {
         PlainFormatter {}
     }
 }
+
+fn print_counters(counters: &TestCounters) {
+    let passed = counters.passed();
+    let skipped = counters.skipped();
+    let failed = counters.failed();
+    let not_run = counters.not_run();
+    let filtered = counters.filtered();
+
+    if passed == 0 && failed == 0 {
+        print!("skipped");
+    } else if failed > 0 {
+        print!("failed");
+    } else {
+        print!("passed");
+    }
+
+    print!(
+        ". {} passed; {} failed; {} skipped",
+        passed, failed, skipped
+    );
+
+    if not_run > 0 {
+        print!("; {} not run", not_run);
+    }
+
+    if filtered > 0 {
+        print!("; {} filtered out", filtered);
+    }
+
+    println!();
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use serde_json;
+
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::case_name;
+use super::seconds;
+use super::suite_name;
+use super::Formatter;
+use super::TestCounters;
+
+#[derive(Debug)]
+pub struct TapFormatter {
+    n_tests: usize,
+}
+
+impl TapFormatter {
+    fn test_ok(&mut self, suite: &TestSuite, case: &TestCase, directive: Option<&str>) {
+        self.n_tests += 1;
+
+        match directive {
+            Some(directive) => println!(
+                "ok {} - {} # {}",
+                self.n_tests,
+                test_name(suite, case),
+                directive
+            ),
+            None => println!("ok {} - {}", self.n_tests, test_name(suite, case)),
+        }
+    }
+
+    fn test_not_ok(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        severity: &str,
+        message: &str,
+        duration: Option<Duration>,
+    ) {
+        self.n_tests += 1;
+
+        println!("not ok {} - {}", self.n_tests, test_name(suite, case));
+        println!("  ---");
+        println!("  message: {}", quote(message));
+        println!("  severity: {}", severity);
+
+        if let Some(duration) = duration {
+            println!("  duration: {}", seconds(duration));
+        }
+
+        println!("  ...");
+    }
+}
+
+impl Formatter for TapFormatter {
+    fn header(&self, n_cases: usize) {
+        println!("TAP version 13");
+        println!("1..{}", n_cases);
+    }
+
+    fn footer(&self, counters: &TestCounters, targets: &[(String, TestCounters)]) {
+        for (target, counters) in targets {
+            println!(
+                "# target {}: {} passed; {} failed; {} skipped; {} not run",
+                target,
+                counters.passed(),
+                counters.failed(),
+                counters.skipped(),
+                counters.not_run()
+            );
+        }
+
+        println!(
+            "# {} passed; {} failed; {} skipped; {} not run; {} filtered out",
+            counters.passed(),
+            counters.failed(),
+            counters.skipped(),
+            counters.not_run(),
+            counters.filtered()
+        );
+    }
+
+    fn suite_started(&mut self, suite: &TestSuite) {
+        println!("# suite {}", suite_name(suite));
+    }
+
+    fn suite_failed(&mut self, suite: &TestSuite, message: &str) {
+        for case in suite.cases() {
+            self.test_not_ok(suite, case, "error", message, None);
+        }
+    }
+
+    fn suite_skipped(&mut self, suite: &TestSuite, _: Duration) {
+        for case in suite.cases() {
+            self.test_ok(suite, case, Some("SKIP suite skipped"));
+        }
+    }
+
+    fn case_passed(&mut self, suite: &TestSuite, case: &TestCase, _: Duration) {
+        self.test_ok(suite, case, None);
+    }
+
+    fn case_failed(&mut self, suite: &TestSuite, case: &TestCase, message: &str, duration: Duration) {
+        self.test_not_ok(suite, case, "fail", message, Some(duration));
+    }
+
+    fn case_errored(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        self.test_not_ok(suite, case, "error", message, Some(duration));
+    }
+
+    fn case_timed_out(
+        &mut self,
+        suite: &TestSuite,
+        case: &TestCase,
+        message: &str,
+        duration: Duration,
+    ) {
+        self.test_not_ok(suite, case, "timeout", message, Some(duration));
+    }
+
+    fn case_skipped(&mut self, suite: &TestSuite, case: &TestCase, _: Duration) {
+        self.test_ok(suite, case, Some("SKIP case skipped"));
+    }
+
+    fn case_not_run(&mut self, suite: &TestSuite, case: &TestCase) {
+        self.test_ok(suite, case, Some("SKIP not run"));
+    }
+}
+
+impl Default for TapFormatter {
+    fn default() -> TapFormatter {
+        TapFormatter { n_tests: 0 }
+    }
+}
+
+fn test_name(suite: &TestSuite, case: &TestCase) -> String {
+    format!("{}::{}", suite_name(suite), case_name(case)).replace('#', "\\#")
+}
+
+fn quote(message: &str) -> String {
+    serde_json::to_string(message).unwrap_or_default()
+}
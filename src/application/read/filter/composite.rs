@@ -0,0 +1,25 @@
+use dto::TestCase;
+use dto::TestSuite;
+
+pub use super::Filter;
+
+#[derive(Debug)]
+pub struct CompositeFilter {
+    filters: Vec<Box<Filter>>,
+}
+
+impl CompositeFilter {
+    pub fn new(filters: Vec<Box<Filter>>) -> CompositeFilter {
+        CompositeFilter { filters }
+    }
+}
+
+impl Filter for CompositeFilter {
+    fn start_suite(&self, suite: &TestSuite) -> bool {
+        self.filters.iter().all(|f| f.start_suite(suite))
+    }
+
+    fn start_case(&self, suite: &TestSuite, case: &TestCase) -> bool {
+        self.filters.iter().all(|f| f.start_case(suite, case))
+    }
+}
@@ -1,3 +1,4 @@
+use dto::TestCase;
 use dto::TestSuite;
 
 pub use super::Filter;
@@ -15,4 +16,8 @@ impl Filter for EmptyFilter {
     fn start_suite(&self, _: &TestSuite) -> bool {
         true
     }
+
+    fn start_case(&self, _: &TestSuite, _: &TestCase) -> bool {
+        true
+    }
 }
@@ -0,0 +1,166 @@
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+#[derive(Debug, PartialEq)]
+pub enum TagExpression {
+    Tag(String),
+    Not(Box<TagExpression>),
+    And(Box<TagExpression>, Box<TagExpression>),
+    Or(Box<TagExpression>, Box<TagExpression>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+type Tokens = Peekable<IntoIter<Token>>;
+
+impl TagExpression {
+    pub fn parse(expression: &str) -> Result<TagExpression, String> {
+        let mut tokens = tokenize(expression).into_iter().peekable();
+        let result = parse_or(&mut tokens)
+            .map_err(|message| format!("Invalid tag expression '{}' - {}", expression, message))?;
+
+        match tokens.next() {
+            Some(token) => Err(format!(
+                "Invalid tag expression '{}' - unexpected {}",
+                expression,
+                token.describe()
+            )),
+            None => Ok(result),
+        }
+    }
+
+    pub fn evaluate(&self, tags: &[&String]) -> bool {
+        match *self {
+            TagExpression::Tag(ref tag) => tags.contains(&tag),
+            TagExpression::Not(ref expression) => !expression.evaluate(tags),
+            TagExpression::And(ref left, ref right) => left.evaluate(tags) && right.evaluate(tags),
+            TagExpression::Or(ref left, ref right) => left.evaluate(tags) || right.evaluate(tags),
+        }
+    }
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match *self {
+            Token::Word(ref word) => format!("tag '{}'", word),
+            Token::And => "'and'".into(),
+            Token::Or => "'or'".into(),
+            Token::Not => "'not'".into(),
+            Token::Open => "'('".into(),
+            Token::Close => "')'".into(),
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+
+    for c in expression.chars() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if !word.is_empty() {
+                tokens.push(word_token(word));
+                word = String::new();
+            }
+
+            match c {
+                '(' => tokens.push(Token::Open),
+                ')' => tokens.push(Token::Close),
+                _ => {}
+            }
+        } else {
+            word.push(c);
+        }
+    }
+
+    if !word.is_empty() {
+        tokens.push(word_token(word));
+    }
+
+    tokens
+}
+
+fn word_token(word: String) -> Token {
+    match word.to_lowercase().as_str() {
+        "and" => Token::And,
+        "or" => Token::Or,
+        "not" => Token::Not,
+        _ => Token::Word(word),
+    }
+}
+
+fn parse_or(tokens: &mut Tokens) -> Result<TagExpression, String> {
+    let mut left = parse_and(tokens)?;
+
+    while tokens.peek() == Some(&Token::Or) {
+        tokens.next();
+        left = TagExpression::Or(Box::new(left), Box::new(parse_and(tokens)?));
+    }
+
+    Ok(left)
+}
+
+fn parse_and(tokens: &mut Tokens) -> Result<TagExpression, String> {
+    let mut left = parse_not(tokens)?;
+
+    while tokens.peek() == Some(&Token::And) {
+        tokens.next();
+        left = TagExpression::And(Box::new(left), Box::new(parse_not(tokens)?));
+    }
+
+    Ok(left)
+}
+
+fn parse_not(tokens: &mut Tokens) -> Result<TagExpression, String> {
+    match tokens.next() {
+        Some(Token::Not) => Ok(TagExpression::Not(Box::new(parse_not(tokens)?))),
+        Some(Token::Word(word)) => Ok(TagExpression::Tag(word)),
+        Some(Token::Open) => {
+            let expression = parse_or(tokens)?;
+
+            match tokens.next() {
+                Some(Token::Close) => Ok(expression),
+                Some(token) => Err(format!("expected ')', but {} found", token.describe())),
+                None => Err("expected ')'".into()),
+            }
+        }
+        Some(token) => Err(format!("expected tag, but {} found", token.describe())),
+        None => Err("expected tag".into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TagExpression;
+
+    #[test]
+    fn parse_and_evaluate_tag_expression() {
+        let smoke = "smoke".to_string();
+        let slow = "slow".to_string();
+        let nightly = "nightly".to_string();
+
+        let expression = TagExpression::parse("smoke and not slow").unwrap();
+        assert!(expression.evaluate(&[&smoke]));
+        assert!(!expression.evaluate(&[&smoke, &slow]));
+        assert!(!expression.evaluate(&[]));
+
+        let expression = TagExpression::parse("nightly or smoke and not slow").unwrap();
+        assert!(expression.evaluate(&[&nightly, &slow]));
+        assert!(!expression.evaluate(&[&smoke, &slow]));
+
+        let expression = TagExpression::parse("(nightly or smoke) and not slow").unwrap();
+        assert!(!expression.evaluate(&[&nightly, &slow]));
+
+        assert!(TagExpression::parse("smoke and").is_err());
+        assert!(TagExpression::parse("(smoke").is_err());
+        assert!(TagExpression::parse("smoke slow").is_err());
+    }
+}
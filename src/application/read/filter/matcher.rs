@@ -0,0 +1,171 @@
+use regex::escape;
+use regex::Regex;
+
+use dto::TestCase;
+use dto::TestSuite;
+
+const PATTERN_SEPARATOR: &str = ",";
+const CASE_SEPARATOR: &str = "::";
+const REGEX_DELIMITER: char = '/';
+const GLOB_WILDCARDS: [char; 2] = ['*', '?'];
+
+#[derive(Debug)]
+pub struct Pattern {
+    suite: Matcher,
+    case: Option<Matcher>,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn parse(expression: &str) -> Result<Pattern, String> {
+        let suite = split(expression, CASE_SEPARATOR)[0];
+        let case = match expression[suite.len()..].strip_prefix(CASE_SEPARATOR) {
+            Some(case) => Some(Matcher::parse(case)?),
+            None => None,
+        };
+        let suite = Matcher::parse(suite)?;
+
+        Ok(Pattern { suite, case })
+    }
+
+    pub fn has_case(&self) -> bool {
+        self.case.is_some()
+    }
+
+    pub fn matches_suite(&self, suite: &TestSuite) -> bool {
+        self.suite.matches(suite.name())
+    }
+
+    pub fn matches_case(&self, suite: &TestSuite, case: &TestCase) -> bool {
+        self.matches_suite(suite) && match self.case {
+            Some(ref matcher) => matcher.matches(case.name()),
+            None => true,
+        }
+    }
+}
+
+impl Matcher {
+    fn parse(expression: &str) -> Result<Matcher, String> {
+        let regex = expression
+            .strip_prefix(REGEX_DELIMITER)
+            .and_then(|value| value.strip_suffix(REGEX_DELIMITER));
+
+        if let Some(regex) = regex {
+            Matcher::regex(expression, regex)
+        } else if expression.contains(&GLOB_WILDCARDS[..]) {
+            Matcher::regex(expression, &glob_to_regex(expression))
+        } else {
+            Ok(Matcher::Substring(expression.into()))
+        }
+    }
+
+    fn regex(expression: &str, regex: &str) -> Result<Matcher, String> {
+        Regex::new(regex)
+            .map(Matcher::Regex)
+            .map_err(|err| format!("Invalid pattern '{}' - {}", expression, err))
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match *self {
+            Matcher::Substring(ref substring) => name.contains(substring.as_str()),
+            Matcher::Regex(ref regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Splits comma-separated sequence of patterns, commas inside regular expressions are kept.
+pub fn split_patterns(expression: &str) -> Vec<&str> {
+    split(expression, PATTERN_SEPARATOR)
+}
+
+/// Splits expression by separator outside of regular expressions. Regular expression
+/// starts with slash at the beginning of pattern or its part and ends with slash
+/// at the end of pattern or its part.
+fn split<'a>(expression: &'a str, separator: &str) -> Vec<&'a str> {
+    let is_boundary = |text: &str| {
+        text.is_empty() || text.starts_with(PATTERN_SEPARATOR) || text.starts_with(CASE_SEPARATOR)
+    };
+    let is_part_start = |index: usize| {
+        index == 0 || expression[..index].ends_with(PATTERN_SEPARATOR)
+            || expression[..index].ends_with(CASE_SEPARATOR)
+    };
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_regex = false;
+
+    for (index, c) in expression.char_indices() {
+        if index < start {
+            continue;
+        }
+
+        if c == REGEX_DELIMITER {
+            if !in_regex && is_part_start(index) {
+                in_regex = true;
+            } else if in_regex && is_boundary(&expression[index + 1..]) {
+                in_regex = false;
+            }
+        } else if !in_regex && expression[index..].starts_with(separator) {
+            parts.push(&expression[start..index]);
+            start = index + separator.len();
+        }
+    }
+
+    parts.push(&expression[start..]);
+    parts
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_patterns;
+    use super::Matcher;
+    use super::Pattern;
+
+    #[test]
+    fn match_substring_glob_and_regex() {
+        let substring = Matcher::parse("user").unwrap();
+        assert!(substring.matches("active users"));
+        assert!(!substring.matches("orders"));
+
+        let glob = Matcher::parse("user*.v?").unwrap();
+        assert!(glob.matches("users.v1"));
+        assert!(!glob.matches("all users.v1"));
+
+        let regex = Matcher::parse("/^order(s|_items)$/").unwrap();
+        assert!(regex.matches("orders"));
+        assert!(regex.matches("order_items"));
+        assert!(!regex.matches("orders_archive"));
+
+        assert!(Matcher::parse("/(/").is_err());
+    }
+
+    #[test]
+    fn split_outside_regex() {
+        assert_eq!(
+            vec!["/^a{1,3}$/", "users::/x{2,}/", "a/b", ""],
+            split_patterns("/^a{1,3}$/,users::/x{2,}/,a/b,")
+        );
+
+        let pattern = Pattern::parse("/^(a|b)::c$/::orders").unwrap();
+        assert!(pattern.has_case());
+    }
+}
@@ -1,22 +1,47 @@
 use std::fmt::Debug;
 
+use application::ApplicationError;
+use application::ApplicationResult;
 use config::Configuration;
+use dto::TestCase;
 use dto::TestSuite;
 
+mod composite;
 mod empty;
-mod simple;
+mod expression;
+mod matcher;
+mod pattern;
+mod tag;
 
+use self::composite::CompositeFilter;
 use self::empty::EmptyFilter;
-use self::simple::SimpleFilter;
+use self::pattern::PatternFilter;
+use self::tag::TagFilter;
 
-pub fn create_filter(config: &Configuration) -> Box<Filter> {
-    if let Some(ref filter) = config.filter() {
-        Box::new(SimpleFilter::create(filter))
-    } else {
-        Box::new(EmptyFilter::default())
+pub fn create_filter(config: &Configuration) -> ApplicationResult<Box<Filter>> {
+    let mut filters: Vec<Box<Filter>> = Vec::new();
+
+    if config.filter().is_some() || !config.exclude().is_empty() {
+        let filter = PatternFilter::create(config.filter(), config.exclude())
+            .map_err(ApplicationError::filter_error)?;
+
+        filters.push(Box::new(filter));
+    }
+
+    if let Some(tags) = config.tags() {
+        let filter = TagFilter::create(tags).map_err(ApplicationError::filter_error)?;
+
+        filters.push(Box::new(filter));
+    }
+
+    match filters.len() {
+        0 => Ok(Box::new(EmptyFilter::default())),
+        1 => Ok(filters.remove(0)),
+        _ => Ok(Box::new(CompositeFilter::new(filters))),
     }
 }
 
 pub trait Filter: Debug {
     fn start_suite(&self, suite: &TestSuite) -> bool;
+    fn start_case(&self, suite: &TestSuite, case: &TestCase) -> bool;
 }
@@ -0,0 +1,62 @@
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::matcher::split_patterns;
+use super::matcher::Pattern;
+pub use super::Filter;
+
+#[derive(Debug)]
+pub struct PatternFilter {
+    include: Option<Vec<Pattern>>,
+    exclude: Vec<Pattern>,
+}
+
+impl PatternFilter {
+    pub fn create(
+        include_expression: Option<&String>,
+        exclude_expressions: &[String],
+    ) -> Result<PatternFilter, String> {
+        let include = match include_expression {
+            Some(expression) => Some(parse_patterns(expression)?),
+            None => None,
+        };
+        let mut exclude = Vec::new();
+
+        for expression in exclude_expressions {
+            exclude.extend(parse_patterns(expression)?);
+        }
+
+        Ok(PatternFilter { include, exclude })
+    }
+}
+
+impl Filter for PatternFilter {
+    fn start_suite(&self, suite: &TestSuite) -> bool {
+        let included = match self.include {
+            Some(ref include) => include.iter().any(|p| p.matches_suite(suite)),
+            None => true,
+        };
+
+        included
+            && !self.exclude
+                .iter()
+                .any(|p| !p.has_case() && p.matches_suite(suite))
+    }
+
+    fn start_case(&self, suite: &TestSuite, case: &TestCase) -> bool {
+        let included = match self.include {
+            Some(ref include) => include.iter().any(|p| p.matches_case(suite, case)),
+            None => true,
+        };
+
+        included && !self.exclude.iter().any(|p| p.matches_case(suite, case))
+    }
+}
+
+fn parse_patterns(expression: &str) -> Result<Vec<Pattern>, String> {
+    split_patterns(expression)
+        .into_iter()
+        .filter(|e| !e.is_empty())
+        .map(Pattern::parse)
+        .collect()
+}
@@ -0,0 +1,30 @@
+use dto::TestCase;
+use dto::TestSuite;
+
+use super::expression::TagExpression;
+pub use super::Filter;
+
+#[derive(Debug)]
+pub struct TagFilter {
+    expression: TagExpression,
+}
+
+impl TagFilter {
+    pub fn create(expression: &str) -> Result<TagFilter, String> {
+        Ok(TagFilter {
+            expression: TagExpression::parse(expression)?,
+        })
+    }
+}
+
+impl Filter for TagFilter {
+    fn start_suite(&self, _: &TestSuite) -> bool {
+        true
+    }
+
+    fn start_case(&self, suite: &TestSuite, case: &TestCase) -> bool {
+        let tags: Vec<&String> = suite.tags().iter().chain(case.tags()).collect();
+
+        self.expression.evaluate(&tags)
+    }
+}
@@ -1,5 +1,6 @@
 use std::fs::File;
 use std::fs;
+use std::mem;
 use std::path::Path;
 
 use serde_yaml;
@@ -18,18 +19,20 @@ pub struct SuiteReader<'a> {
     config: &'a Configuration,
     filter: Box<Filter>,
     suites: Vec<TestSuite>,
+    n_filtered: usize,
 }
 
 impl<'a> SuiteReader<'a> {
-    pub fn new(config: &'a Configuration) -> SuiteReader<'a> {
-        SuiteReader {
+    pub fn new(config: &'a Configuration) -> ApplicationResult<SuiteReader<'a>> {
+        Ok(SuiteReader {
             config,
-            filter: filter::create_filter(config),
+            filter: filter::create_filter(config)?,
             suites: Vec::default(),
-        }
+            n_filtered: 0,
+        })
     }
 
-    pub fn read(mut self) -> ApplicationResult<Vec<TestSuite>> {
+    pub fn read(&mut self) -> ApplicationResult<Vec<TestSuite>> {
         let recursive = self.config.recursive();
         let extensions = self.config.extensions();
 
@@ -39,7 +42,7 @@ impl<'a> SuiteReader<'a> {
                 let suite =
                     serde_yaml::from_reader(reader).map_err(ApplicationError::suite_yaml_error)?;
 
-                self.add_suite(suite);
+                self.add_suite(path, suite)?;
             } else if recursive && path.is_dir() {
                 self.read_recursively(path, extensions)?;
             } else {
@@ -48,16 +51,44 @@ impl<'a> SuiteReader<'a> {
         }
 
         if !self.suites.is_empty() {
-            Ok(self.suites)
+            Ok(mem::take(&mut self.suites))
         } else {
             Err(ApplicationError::no_suites_found())
         }
     }
 
-    fn add_suite(&mut self, suite: TestSuite) {
+    fn add_suite(&mut self, path: &Path, mut suite: TestSuite) -> ApplicationResult<()> {
         if self.filter.start_suite(&suite) {
-            self.suites.push(suite);
+            suite.set_path(path);
+            suite.set_default_timeout(self.config.timeout());
+            suite
+                .expand_parameters()
+                .map_err(|message| ApplicationError::suite_variable_error(suite.name(), message))?;
+            suite
+                .resolve_variables(self.config.variables())
+                .map_err(|message| ApplicationError::suite_variable_error(suite.name(), message))?;
+
+            let (cases, filtered): (Vec<_>, Vec<_>) = suite
+                .cases()
+                .iter()
+                .cloned()
+                .partition(|case| self.filter.start_case(&suite, case));
+
+            self.n_filtered += filtered.len();
+
+            if !cases.is_empty() {
+                suite.set_cases(cases);
+                self.suites.push(suite);
+            }
+        } else {
+            self.n_filtered += suite.cases().len();
         }
+
+        Ok(())
+    }
+
+    pub fn n_filtered(&self) -> usize {
+        self.n_filtered
     }
 
     fn read_recursively(
@@ -73,11 +104,11 @@ impl<'a> SuiteReader<'a> {
             let path = entry.path();
 
             if metadata.is_file() && is_extension_matches(&path, extensions) {
-                let reader = File::open(path).map_err(ApplicationError::suite_io_error)?;
+                let reader = File::open(&path).map_err(ApplicationError::suite_io_error)?;
                 let suite =
                     serde_yaml::from_reader(reader).map_err(ApplicationError::suite_yaml_error)?;
 
-                self.add_suite(suite);
+                self.add_suite(&path, suite)?;
             } else if metadata.is_dir() {
                 self.read_recursively(&path, extensions)?;
             }
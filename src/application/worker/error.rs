@@ -12,10 +12,15 @@ pub type WorkerResult<T> = Result<T, WorkerError>;
 pub enum WorkerError {
     SpawnIoError { message: String },
     ConnectError { message: String },
-    DatabaseError { code: String, message: String },
+    DatabaseError {
+        code: String,
+        message: String,
+        constraint: Option<String>,
+    },
     IoError { message: String },
     ConversionError { message: String },
-    UnknownError,
+    TlsError { message: String },
+    UnknownError { message: String },
 }
 
 impl WorkerError {
@@ -24,6 +29,31 @@ impl WorkerError {
             message: format!("{}", error),
         }
     }
+
+    pub fn tls_error<E>(context: &str, error: E) -> WorkerError
+    where
+        E: Error,
+    {
+        WorkerError::TlsError {
+            message: format!("{} - {}", context, error),
+        }
+    }
+
+    /// Converts error of connection opening. Failed TLS handshake is the only error
+    /// kind which can not be recognized with `postgres::Error` accessors.
+    pub fn connect_error(error: PgError) -> WorkerError {
+        if error.as_connection().is_none()
+            && error.as_db().is_none()
+            && error.as_conversion().is_none()
+            && error.as_io().is_none()
+        {
+            return WorkerError::TlsError {
+                message: format!("{}", error),
+            };
+        }
+
+        error.into()
+    }
 }
 
 impl Display for WorkerError {
@@ -34,10 +64,12 @@ impl Display for WorkerError {
             WorkerError::DatabaseError {
                 ref code,
                 ref message,
+                ..
             } => write!(f, "{}: {}", code, message),
             WorkerError::IoError { ref message } => write!(f, "{}", message),
             WorkerError::ConversionError { ref message } => write!(f, "{}", message),
-            WorkerError::UnknownError => write!(f, "Unknown error"),
+            WorkerError::TlsError { ref message } => write!(f, "{}", message),
+            WorkerError::UnknownError { ref message } => write!(f, "{}", message),
         }
     }
 }
@@ -50,6 +82,7 @@ impl Error for WorkerError {
             WorkerError::DatabaseError { .. } => "Database error",
             WorkerError::IoError { .. } => "IO error",
             WorkerError::ConversionError { .. } => "Conversion error",
+            WorkerError::TlsError { .. } => "TLS error",
             WorkerError::UnknownError { .. } => "Unknown error",
         }
     }
@@ -59,23 +92,26 @@ impl From<PgError> for WorkerError {
     fn from(error: PgError) -> WorkerError {
         if let Some(err) = error.as_connection() {
             WorkerError::ConnectError {
-                message: err.description().into(),
+                message: format!("{}", err),
             }
         } else if let Some(err) = error.as_db() {
             WorkerError::DatabaseError {
                 code: err.code.code().into(),
                 message: err.message.clone(),
+                constraint: err.constraint.clone(),
             }
         } else if let Some(err) = error.as_conversion() {
             WorkerError::ConversionError {
-                message: err.description().into(),
+                message: format!("{}", err),
             }
         } else if let Some(err) = error.as_io() {
             WorkerError::IoError {
-                message: err.description().into(),
+                message: format!("{}", err),
             }
         } else {
-            WorkerError::UnknownError
+            WorkerError::UnknownError {
+                message: format!("{}", error),
+            }
         }
     }
 }
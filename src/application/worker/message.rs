@@ -1,45 +1,112 @@
+use std::time::Duration;
+
 use dto::QueryClause;
 use dto::TestCase;
+use dto::TestSuite;
+
+use super::reply::WorkerReply;
 
 #[derive(Debug)]
 pub enum WorkerMessage {
     SuiteSkip {
         suite_index: usize,
         clause: QueryClause,
+        timeout: Option<Duration>,
     },
     CaseSkip {
         suite_index: usize,
         case_index: usize,
         clause: QueryClause,
+        timeout: Option<Duration>,
     },
     CaseRun {
         suite_index: usize,
         case_index: usize,
         case: TestCase,
+        setup: Vec<String>,
+        teardown: Vec<String>,
+        max_duration: Option<Duration>,
+        timeout: Option<Duration>,
     },
 }
 
 impl WorkerMessage {
-    pub fn suite_skip(suite_index: usize, clause: &QueryClause) -> WorkerMessage {
+    pub fn suite_skip(
+        suite_index: usize,
+        suite: &TestSuite,
+        clause: &QueryClause,
+    ) -> WorkerMessage {
         WorkerMessage::SuiteSkip {
             suite_index,
             clause: clause.clone(),
+            timeout: suite.timeout().map(|limit| limit.duration()),
         }
     }
 
-    pub fn case_skip(suite_index: usize, case_index: usize, clause: &QueryClause) -> WorkerMessage {
+    pub fn case_skip(
+        suite_index: usize,
+        case_index: usize,
+        suite: &TestSuite,
+        case: &TestCase,
+        clause: &QueryClause,
+    ) -> WorkerMessage {
         WorkerMessage::CaseSkip {
             suite_index,
             case_index,
             clause: clause.clone(),
+            timeout: case_timeout(suite, case),
         }
     }
 
-    pub fn case_run(suite_index: usize, case_index: usize, case: &TestCase) -> WorkerMessage {
+    pub fn case_run(
+        suite_index: usize,
+        case_index: usize,
+        suite: &TestSuite,
+        case: &TestCase,
+    ) -> WorkerMessage {
+        let mut setup = Vec::new();
+        let mut teardown = Vec::new();
+
+        for script in suite.setup().iter().chain(case.setup().iter()) {
+            setup.extend(script.statements());
+        }
+
+        for script in case.teardown().iter().chain(suite.teardown().iter()) {
+            teardown.extend(script.statements());
+        }
+
         WorkerMessage::CaseRun {
             suite_index,
             case_index,
             case: case.clone(),
+            setup,
+            teardown,
+            max_duration: case.max_duration()
+                .or_else(|| suite.max_duration())
+                .map(|limit| limit.duration()),
+            timeout: case_timeout(suite, case),
+        }
+    }
+
+    pub fn not_run(&self) -> WorkerReply {
+        match *self {
+            WorkerMessage::SuiteSkip { suite_index, .. } => WorkerReply::not_run(suite_index, None),
+            WorkerMessage::CaseSkip {
+                suite_index,
+                case_index,
+                ..
+            }
+            | WorkerMessage::CaseRun {
+                suite_index,
+                case_index,
+                ..
+            } => WorkerReply::not_run(suite_index, Some(case_index)),
         }
     }
 }
+
+fn case_timeout(suite: &TestSuite, case: &TestCase) -> Option<Duration> {
+    case.timeout()
+        .or_else(|| suite.timeout())
+        .map(|limit| limit.duration())
+}
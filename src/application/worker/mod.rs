@@ -4,34 +4,50 @@ use std::sync::mpsc::SyncSender;
 use std::sync::Mutex;
 use std::thread::Builder;
 use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
 
 use postgres::Connection;
-use postgres::TlsMode;
+use postgres::Error as PgError;
+use postgres::error::QUERY_CANCELED;
+use postgres::params::ConnectParams;
+use postgres::Result as PgResult;
+use postgres::rows::Rows;
+use postgres::types::ToSql;
+use postgres::transaction::Transaction;
 
+use dto::CompareClause;
 use dto::QueryClause;
 use dto::TestCase;
 
 mod error;
 mod message;
+mod peer;
 mod query;
 mod reply;
+mod tls;
 mod util;
+mod value;
 
 pub use self::error::WorkerError;
 pub use self::error::WorkerResult;
 pub use self::message::WorkerMessage;
+pub use self::peer::Peer;
 pub use self::query::QueryResult;
 pub use self::reply::WorkerReply;
+pub use self::tls::TlsConfig;
+
+use self::peer::PeerConnections;
+
+const CASE_SAVEPOINT: &str = "database_test_case";
 
 #[derive(Debug)]
 pub struct Worker {
     message_channel: Arc<Mutex<Receiver<WorkerMessage>>>,
     reply_channel: SyncSender<WorkerReply>,
-    hostname: String,
-    port: u16,
-    database: String,
-    username: String,
-    password: String,
+    params: ConnectParams,
+    tls: TlsConfig,
+    peers: Vec<Peer>,
 }
 
 macro_rules! query_result {
@@ -56,29 +72,29 @@ impl Worker {
     pub fn new(
         message_channel: Arc<Mutex<Receiver<WorkerMessage>>>,
         reply_channel: SyncSender<WorkerReply>,
-        hostname: &str,
-        port: u16,
-        database: &str,
-        username: &str,
-        password: &str,
+        params: ConnectParams,
     ) -> Worker {
         Worker {
             message_channel,
             reply_channel,
-            hostname: hostname.into(),
-            port,
-            database: database.into(),
-            username: username.into(),
-            password: password.into(),
+            params,
+            tls: TlsConfig::Disabled,
+            peers: Vec::new(),
         }
     }
 
+    pub fn with_peers(mut self, peers: Vec<Peer>) -> Worker {
+        self.peers = peers;
+        self
+    }
+
+    pub fn with_tls(mut self, tls: TlsConfig) -> Worker {
+        self.tls = tls;
+        self
+    }
+
     pub fn start(self) -> WorkerResult<JoinHandle<()>> {
-        let url = format!(
-            "postgresql://{3}:{4}@{0}:{1}/{2}",
-            self.hostname, self.port, self.database, self.username, self.password,
-        );
-        let connection = Connection::connect(url, TlsMode::None)?;
+        let connection = self.tls.connect(&self.params)?;
 
         Builder::new()
             .spawn(|| self.run(connection))
@@ -86,37 +102,63 @@ impl Worker {
     }
 
     fn run(self, connection: Connection) {
+        let mut peers = PeerConnections::new(self.peers.clone());
+
         while let Ok(message) = self.next_message() {
-            match message {
-                WorkerMessage::SuiteSkip {
-                    suite_index,
-                    ref clause,
-                } => {
-                    let result = Worker::execute_clause(&connection, clause);
-                    let reply = WorkerReply::suite_skip(suite_index, result);
-
-                    self.reply_channel.send(reply).unwrap();
-                }
-                WorkerMessage::CaseSkip {
-                    suite_index,
-                    case_index,
-                    ref clause,
-                } => {
-                    let result = Worker::execute_clause(&connection, clause);
-                    let reply = WorkerReply::case_skip(suite_index, case_index, result);
-
-                    self.reply_channel.send(reply).unwrap();
-                }
-                WorkerMessage::CaseRun {
-                    suite_index,
-                    case_index,
-                    ref case,
-                } => {
-                    let result = Worker::execute_case(&connection, case);
-                    let reply = WorkerReply::case_run(suite_index, case_index, result);
-
-                    self.reply_channel.send(reply).unwrap();
-                }
+            let reply = Worker::execute_message(&connection, &mut peers, &message);
+
+            self.reply_channel.send(reply).unwrap();
+        }
+    }
+
+    fn execute_message(
+        connection: &Connection,
+        peers: &mut PeerConnections,
+        message: &WorkerMessage,
+    ) -> WorkerReply {
+        match *message {
+            WorkerMessage::SuiteSkip {
+                suite_index,
+                ref clause,
+                timeout,
+            } => {
+                let start = Instant::now();
+                let result = Worker::execute_clause(connection, clause, timeout);
+
+                WorkerReply::suite_skip(suite_index, result, start.elapsed())
+            }
+            WorkerMessage::CaseSkip {
+                suite_index,
+                case_index,
+                ref clause,
+                timeout,
+            } => {
+                let start = Instant::now();
+                let result = Worker::execute_clause(connection, clause, timeout);
+
+                WorkerReply::case_skip(suite_index, case_index, result, start.elapsed())
+            }
+            WorkerMessage::CaseRun {
+                suite_index,
+                case_index,
+                ref case,
+                ref setup,
+                ref teardown,
+                max_duration,
+                timeout,
+            } => {
+                let start = Instant::now();
+                let result = Worker::execute_case(
+                    connection,
+                    peers,
+                    case,
+                    setup,
+                    teardown,
+                    max_duration,
+                    timeout,
+                );
+
+                WorkerReply::case_run(suite_index, case_index, result, start.elapsed())
             }
         }
     }
@@ -127,12 +169,104 @@ impl Worker {
         guard.recv().map_err(|_| ())
     }
 
-    fn execute_case(connection: &Connection, case: &TestCase) -> QueryResult {
-        let query = case.query();
+    fn execute_case(
+        connection: &Connection,
+        peers: &mut PeerConnections,
+        case: &TestCase,
+        setup: &[String],
+        teardown: &[String],
+        max_duration: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> QueryResult {
+        let peer_rows = match case.compare() {
+            Some(compare) => match Worker::query_peer(peers, compare, case, setup, timeout) {
+                Ok(rows) => Some(rows),
+                Err(result) => {
+                    peers.disconnect(compare.with());
+
+                    return result;
+                }
+            },
+            None => None,
+        };
         let transaction = try_query_result!(connection.transaction());
         transaction.set_rollback();
+        try_query_result!(Worker::set_timeout(&transaction, timeout));
 
-        let rows = try_query_result!(transaction.query(query, &[]));
+        for statement in setup {
+            let start = Instant::now();
+
+            if let Err(err) = transaction.batch_execute(statement) {
+                if let Some(result) = Worker::timed_out(&err, timeout, start.elapsed()) {
+                    return result;
+                }
+
+                return QueryResult::setup_error(format!(
+                    "Setup failed - {}",
+                    WorkerError::from(err)
+                ));
+            }
+        }
+
+        let result = match transaction.savepoint(CASE_SAVEPOINT) {
+            Ok(savepoint) => Worker::execute_query(
+                &savepoint,
+                case,
+                max_duration,
+                timeout,
+                peer_rows.as_ref(),
+            ),
+            Err(err) => err.into(),
+        };
+
+        for statement in teardown {
+            if let Err(err) = transaction.batch_execute(statement) {
+                if result == QueryResult::Success {
+                    return QueryResult::setup_error(format!(
+                        "Teardown failed - {}",
+                        WorkerError::from(err)
+                    ));
+                }
+
+                break;
+            }
+        }
+
+        result
+    }
+
+    fn execute_query(
+        savepoint: &Transaction,
+        case: &TestCase,
+        max_duration: Option<Duration>,
+        timeout: Option<Duration>,
+        peer_rows: Option<&Rows>,
+    ) -> QueryResult {
+        let start = Instant::now();
+        let rows = match Worker::query_case(savepoint, case) {
+            Ok(Ok(rows)) => rows,
+            Ok(Err(message)) => return QueryResult::error(message),
+            Err(err) => {
+                savepoint.set_rollback();
+
+                let timeout_result = Worker::timed_out(&err, timeout, start.elapsed());
+                let result = match case.expect_error() {
+                    Some(expect_error) => util::assert_error(err.into(), expect_error),
+                    None => err.into(),
+                };
+
+                return match timeout_result {
+                    Some(timeout_result) if result != QueryResult::Success => timeout_result,
+                    _ => result,
+                };
+            }
+        };
+
+        let duration = start.elapsed();
+
+        if case.expect_error().is_some() {
+            return QueryResult::fail("Query succeeded, but error expected");
+        }
 
         if let Some(n_rows) = case.n_rows() {
             query_result!(util::assert_n_rows(rows.len(), n_rows));
@@ -146,14 +280,169 @@ impl Worker {
             }
         }
 
+        for aggregate in case.aggregates() {
+            query_result!(util::assert_aggregate(&rows, aggregate));
+        }
+
+        if !case.unique().is_empty() {
+            query_result!(util::assert_unique(&rows, case.unique()));
+        }
+
+        if let Some(expect_rows) = case.expect_rows() {
+            query_result!(util::assert_rows(&rows, expect_rows));
+        }
+
+        if let Some(compare_with) = case.compare_with() {
+            let start = Instant::now();
+            let other_rows = match Worker::query_bound(savepoint, compare_with.query(), case) {
+                Ok(Ok(other_rows)) => other_rows,
+                Ok(Err(message)) => {
+                    return QueryResult::error(format!("Compare query failed - {}", message))
+                }
+                Err(err) => {
+                    savepoint.set_rollback();
+
+                    return Worker::timed_out(&err, timeout, start.elapsed()).unwrap_or_else(|| {
+                        QueryResult::error(format!(
+                            "Compare query failed - {}",
+                            WorkerError::from(err)
+                        ))
+                    });
+                }
+            };
+
+            query_result!(util::assert_compare_with(&rows, &other_rows, compare_with));
+        }
+
+        if let (Some(compare), Some(peer_rows)) = (case.compare(), peer_rows) {
+            query_result!(util::assert_compare(&rows, peer_rows, compare));
+        }
+
+        if let Some(max_duration) = max_duration {
+            query_result!(util::assert_duration(duration, max_duration));
+        }
+
         QueryResult::Success
     }
 
-    fn execute_clause(connection: &Connection, clause: &QueryClause) -> QueryResult {
-        let query = clause.query();
-        let rows = try_query_result!(connection.query(query, &[]));
-        let actual_rows = rows.len();
+    fn query_peer(
+        peers: &mut PeerConnections,
+        compare: &CompareClause,
+        case: &TestCase,
+        setup: &[String],
+        timeout: Option<Duration>,
+    ) -> Result<Rows, QueryResult> {
+        let name = compare.with();
+        let peer_error = |err: WorkerError| {
+            QueryResult::error(format!("Compare with {} failed - {}", name, err))
+        };
+        let connection = peers.connection(name).map_err(&peer_error)?;
+        let transaction = connection.transaction().map_err(|err| peer_error(err.into()))?;
+        transaction.set_rollback();
+        Worker::set_timeout(&transaction, timeout).map_err(|err| peer_error(err.into()))?;
+
+        for statement in setup {
+            let start = Instant::now();
+
+            if let Err(err) = transaction.batch_execute(statement) {
+                return Err(Worker::timed_out(&err, timeout, start.elapsed()).unwrap_or_else(|| {
+                    QueryResult::setup_error(format!(
+                        "Setup failed on {} - {}",
+                        name,
+                        WorkerError::from(err)
+                    ))
+                }));
+            }
+        }
+
+        // Setup may change data, the query itself can not.
+        transaction
+            .batch_execute("SET TRANSACTION READ ONLY")
+            .map_err(|err| peer_error(err.into()))?;
+
+        let start = Instant::now();
+
+        match Worker::query_case(&transaction, case) {
+            Ok(Ok(rows)) => Ok(rows),
+            Ok(Err(message)) => Err(QueryResult::error(message)),
+            Err(err) => Err(Worker::timed_out(&err, timeout, start.elapsed())
+                .unwrap_or_else(|| peer_error(err.into()))),
+        }
+    }
+
+    fn set_timeout(transaction: &Transaction, timeout: Option<Duration>) -> PgResult<()> {
+        match timeout {
+            Some(timeout) => transaction.batch_execute(&format!(
+                "SET LOCAL statement_timeout = {}",
+                util::as_millis(timeout)
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Statement is considered timed out if it was cancelled after the timeout elapsed,
+    /// otherwise it was cancelled by other reason, e.g. by `pg_cancel_backend`.
+    fn timed_out(
+        error: &PgError,
+        timeout: Option<Duration>,
+        elapsed: Duration,
+    ) -> Option<QueryResult> {
+        match timeout {
+            Some(timeout) if error.code() == Some(&QUERY_CANCELED) && elapsed >= timeout => {
+                Some(QueryResult::timeout(format!(
+                    "Timed out after {} ms",
+                    util::as_millis(timeout)
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    fn query_case(
+        savepoint: &Transaction,
+        case: &TestCase,
+    ) -> PgResult<Result<Rows, String>> {
+        Worker::query_bound(savepoint, case.query(), case)
+    }
+
+    /// Executes query with bind variables of test case.
+    fn query_bound(
+        savepoint: &Transaction,
+        query: &str,
+        case: &TestCase,
+    ) -> PgResult<Result<Rows, String>> {
+        if case.bind().is_empty() {
+            return savepoint.query(query, &[]).map(Ok);
+        }
+
+        let statement = savepoint.prepare(query)?;
+        let parameters = match util::bind_parameters(statement.param_types(), case) {
+            Ok(parameters) => parameters,
+            Err(message) => return Ok(Err(message)),
+        };
+        let parameters: Vec<&ToSql> = parameters.iter().map(|value| value as &ToSql).collect();
+
+        statement.query(&parameters).map(Ok)
+    }
+
+    fn execute_clause(
+        connection: &Connection,
+        clause: &QueryClause,
+        timeout: Option<Duration>,
+    ) -> QueryResult {
+        let transaction = try_query_result!(connection.transaction());
+        transaction.set_commit();
+        try_query_result!(Worker::set_timeout(&transaction, timeout));
+
+        let start = Instant::now();
+        let rows = match transaction.query(clause.query(), &[]) {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Worker::timed_out(&err, timeout, start.elapsed())
+                    .unwrap_or_else(|| err.into())
+            }
+        };
 
-        util::assert_n_rows(actual_rows, clause.n_rows())
+        util::assert_n_rows(rows.len(), clause.n_rows())
     }
 }
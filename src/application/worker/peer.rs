@@ -0,0 +1,63 @@
+use postgres::params::ConnectParams;
+use postgres::Connection;
+
+use super::error::WorkerError;
+use super::error::WorkerResult;
+use super::tls::TlsConfig;
+
+/// Connection parameters of another target, used by comparison cases.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    name: String,
+    params: ConnectParams,
+    tls: TlsConfig,
+}
+
+/// Connections to peer targets, every connection is opened on first use.
+#[derive(Debug)]
+pub struct PeerConnections {
+    peers: Vec<Peer>,
+    connections: Vec<(String, Connection)>,
+}
+
+impl Peer {
+    pub fn new(name: &str, params: ConnectParams, tls: TlsConfig) -> Peer {
+        Peer {
+            name: name.into(),
+            params,
+            tls,
+        }
+    }
+}
+
+impl PeerConnections {
+    pub fn new(peers: Vec<Peer>) -> PeerConnections {
+        PeerConnections {
+            peers,
+            connections: Vec::new(),
+        }
+    }
+
+    pub fn connection(&mut self, name: &str) -> WorkerResult<&Connection> {
+        if let Some(index) = self.connections.iter().position(|(item, _)| item == name) {
+            return Ok(&self.connections[index].1);
+        }
+
+        let peer = self.peers
+            .iter()
+            .find(|peer| peer.name == name)
+            .ok_or_else(|| WorkerError::ConnectError {
+                message: format!("Unknown target {}", name),
+            })?;
+        let connection = peer.tls.connect(&peer.params)?;
+
+        self.connections.push((name.into(), connection));
+
+        Ok(&self.connections[self.connections.len() - 1].1)
+    }
+
+    /// Closes connection after error, so next case opens new one.
+    pub fn disconnect(&mut self, name: &str) {
+        self.connections.retain(|(item, _)| item != name);
+    }
+}
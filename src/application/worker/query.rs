@@ -5,6 +5,8 @@ pub enum QueryResult {
     Success,
     Fail { message: String },
     Error { message: String },
+    SetupError { message: String },
+    Timeout { message: String },
 }
 
 impl QueryResult {
@@ -22,6 +24,36 @@ impl QueryResult {
             message: message.into(),
         }
     }
+
+    #[inline]
+    pub fn error<S>(message: S) -> QueryResult
+    where
+        S: Into<String>,
+    {
+        QueryResult::Error {
+            message: message.into(),
+        }
+    }
+
+    #[inline]
+    pub fn setup_error<S>(message: S) -> QueryResult
+    where
+        S: Into<String>,
+    {
+        QueryResult::SetupError {
+            message: message.into(),
+        }
+    }
+
+    #[inline]
+    pub fn timeout<S>(message: S) -> QueryResult
+    where
+        S: Into<String>,
+    {
+        QueryResult::Timeout {
+            message: message.into(),
+        }
+    }
 }
 
 impl<E> From<E> for QueryResult
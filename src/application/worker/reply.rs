@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use super::query::QueryResult;
 
 #[derive(Debug)]
@@ -5,40 +7,89 @@ pub enum WorkerReply {
     SuiteSkip {
         suite_index: usize,
         result: QueryResult,
+        duration: Duration,
     },
     CaseSkip {
         suite_index: usize,
         case_index: usize,
         result: QueryResult,
+        duration: Duration,
     },
     CaseRun {
         suite_index: usize,
         case_index: usize,
         result: QueryResult,
+        duration: Duration,
+    },
+    NotRun {
+        suite_index: usize,
+        case_index: Option<usize>,
     },
 }
 
 impl WorkerReply {
-    pub fn suite_skip(suite_index: usize, result: QueryResult) -> WorkerReply {
+    pub fn suite_skip(suite_index: usize, result: QueryResult, duration: Duration) -> WorkerReply {
         WorkerReply::SuiteSkip {
             suite_index,
             result,
+            duration,
         }
     }
 
-    pub fn case_skip(suite_index: usize, case_index: usize, result: QueryResult) -> WorkerReply {
+    pub fn case_skip(
+        suite_index: usize,
+        case_index: usize,
+        result: QueryResult,
+        duration: Duration,
+    ) -> WorkerReply {
         WorkerReply::CaseSkip {
             suite_index,
             case_index,
             result,
+            duration,
         }
     }
 
-    pub fn case_run(suite_index: usize, case_index: usize, result: QueryResult) -> WorkerReply {
+    pub fn case_run(
+        suite_index: usize,
+        case_index: usize,
+        result: QueryResult,
+        duration: Duration,
+    ) -> WorkerReply {
         WorkerReply::CaseRun {
             suite_index,
             case_index,
             result,
+            duration,
+        }
+    }
+
+    pub fn suite_index(&self) -> usize {
+        match *self {
+            WorkerReply::SuiteSkip { suite_index, .. }
+            | WorkerReply::CaseSkip { suite_index, .. }
+            | WorkerReply::CaseRun { suite_index, .. }
+            | WorkerReply::NotRun { suite_index, .. } => suite_index,
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        match *self {
+            WorkerReply::SuiteSkip { ref result, .. } | WorkerReply::CaseSkip { ref result, .. } => {
+                match *result {
+                    QueryResult::Success | QueryResult::Fail { .. } => false,
+                    _ => true,
+                }
+            }
+            WorkerReply::CaseRun { ref result, .. } => *result != QueryResult::Success,
+            WorkerReply::NotRun { .. } => false,
+        }
+    }
+
+    pub fn not_run(suite_index: usize, case_index: Option<usize>) -> WorkerReply {
+        WorkerReply::NotRun {
+            suite_index,
+            case_index,
         }
     }
 }
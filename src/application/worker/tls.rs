@@ -0,0 +1,202 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::Read;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use openssl::ssl::SslConnector;
+use openssl::ssl::SslFiletype;
+use openssl::ssl::SslMethod;
+use openssl::ssl::SslStream;
+use openssl::ssl::SslVerifyMode;
+use postgres::params::ConnectParams;
+use postgres::tls::Stream;
+use postgres::tls::TlsHandshake;
+use postgres::tls::TlsStream;
+use postgres::Connection;
+use postgres::TlsMode;
+
+use config::SslMode;
+
+use super::error::WorkerError;
+use super::error::WorkerResult;
+
+#[derive(Debug, Clone)]
+pub enum TlsConfig {
+    Disabled,
+    Allow(Arc<OpenSslHandshake>),
+    Prefer(Arc<OpenSslHandshake>),
+    Require(Arc<OpenSslHandshake>),
+}
+
+pub struct OpenSslHandshake {
+    connector: SslConnector,
+    verify_hostname: bool,
+}
+
+#[derive(Debug)]
+struct OpenSslStream(SslStream<Stream>);
+
+impl TlsConfig {
+    pub fn new(
+        ssl_mode: SslMode,
+        root_cert: Option<&PathBuf>,
+        cert: Option<&PathBuf>,
+        key: Option<&PathBuf>,
+    ) -> WorkerResult<TlsConfig> {
+        let verify_ca = match ssl_mode {
+            SslMode::Disable => return Ok(TlsConfig::Disabled),
+            SslMode::Allow | SslMode::Prefer => false,
+            SslMode::Require => root_cert.is_some(),
+            SslMode::VerifyCa | SslMode::VerifyFull => true,
+        };
+        let verify_hostname = ssl_mode == SslMode::VerifyFull;
+        let handshake = OpenSslHandshake::new(verify_ca, verify_hostname, root_cert, cert, key)?;
+
+        match ssl_mode {
+            SslMode::Allow => Ok(TlsConfig::Allow(Arc::new(handshake))),
+            SslMode::Prefer => Ok(TlsConfig::Prefer(Arc::new(handshake))),
+            _ => Ok(TlsConfig::Require(Arc::new(handshake))),
+        }
+    }
+
+    /// Opens connection, in `allow` mode connection is retried with TLS if server
+    /// rejects unencrypted connection.
+    pub fn connect(&self, params: &ConnectParams) -> WorkerResult<Connection> {
+        let result = Connection::connect(params.clone(), self.mode());
+
+        if let TlsConfig::Allow(ref handshake) = *self {
+            let is_rejected = match result {
+                Err(ref err) => err.as_db().is_some(),
+                Ok(_) => false,
+            };
+
+            if is_rejected {
+                return Connection::connect(params.clone(), TlsMode::Require(&**handshake))
+                    .map_err(WorkerError::connect_error);
+            }
+        }
+
+        result.map_err(WorkerError::connect_error)
+    }
+
+    fn mode(&self) -> TlsMode<'_> {
+        match *self {
+            TlsConfig::Disabled | TlsConfig::Allow(_) => TlsMode::None,
+            TlsConfig::Prefer(ref handshake) => TlsMode::Prefer(&**handshake),
+            TlsConfig::Require(ref handshake) => TlsMode::Require(&**handshake),
+        }
+    }
+}
+
+impl OpenSslHandshake {
+    fn new(
+        verify_ca: bool,
+        verify_hostname: bool,
+        root_cert: Option<&PathBuf>,
+        cert: Option<&PathBuf>,
+        key: Option<&PathBuf>,
+    ) -> WorkerResult<OpenSslHandshake> {
+        let mut builder = SslConnector::builder(SslMethod::tls())
+            .map_err(|err| WorkerError::tls_error("Can not create TLS connector", err))?;
+
+        if verify_ca {
+            if let Some(root_cert) = root_cert {
+                builder
+                    .set_ca_file(root_cert)
+                    .map_err(|err| file_error("root certificate", root_cert, err))?;
+            }
+        } else {
+            builder.set_verify(SslVerifyMode::NONE);
+        }
+
+        if let Some(cert) = cert {
+            let key = key.unwrap_or(cert);
+
+            builder
+                .set_certificate_chain_file(cert)
+                .map_err(|err| file_error("client certificate", cert, err))?;
+            builder
+                .set_private_key_file(key, SslFiletype::PEM)
+                .map_err(|err| file_error("client key", key, err))?;
+            builder
+                .check_private_key()
+                .map_err(|err| file_error("client key", key, err))?;
+        }
+
+        Ok(OpenSslHandshake {
+            connector: builder.build(),
+            verify_hostname,
+        })
+    }
+}
+
+impl Debug for OpenSslHandshake {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("OpenSslHandshake")
+            .field("verify_hostname", &self.verify_hostname)
+            .finish()
+    }
+}
+
+impl TlsHandshake for OpenSslHandshake {
+    fn tls_handshake(
+        &self,
+        host: &str,
+        stream: Stream,
+    ) -> Result<Box<TlsStream>, Box<Error + Sync + Send>> {
+        let mut config = self.connector.configure()?;
+
+        config.set_verify_hostname(self.verify_hostname);
+
+        let stream = config
+            .connect(host, stream)
+            .map_err(|err| format!("{}", err))?;
+
+        Ok(Box::new(OpenSslStream(stream)))
+    }
+}
+
+impl Read for OpenSslStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for OpenSslStream {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.0.flush()
+    }
+}
+
+impl TlsStream for OpenSslStream {
+    fn get_ref(&self) -> &Stream {
+        self.0.get_ref()
+    }
+
+    fn get_mut(&mut self) -> &mut Stream {
+        self.0.get_mut()
+    }
+}
+
+fn file_error<E>(name: &str, path: &Path, error: E) -> WorkerError
+where
+    E: Error,
+{
+    if path.is_file() {
+        WorkerError::tls_error(&format!("Can not load {} {}", name, path.display()), error)
+    } else {
+        WorkerError::TlsError {
+            message: format!("Can not load {} {} - file not found", name, path.display()),
+        }
+    }
+}
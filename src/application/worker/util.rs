@@ -1,16 +1,34 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::Entry;
 use std::fmt::Display;
+use std::time::Duration;
 
-use postgres::Result as PgResult;
 use postgres::rows::Row;
-use postgres::types::FromSql;
+use postgres::rows::Rows;
+use postgres::types::Type;
 
+use dto::AggregateClause;
+use dto::AggregateFunction;
 use dto::ColumnClause;
+use dto::CompareClause;
+use dto::CompareWithClause;
 use dto::Condition;
+use dto::ErrorClause;
 use dto::NRowsClause;
+use dto::NullPolicy;
+use dto::RowsClause;
+use dto::TestCase;
 use dto::Value;
-use dto::Values;
 
+use super::value::ColumnValue;
+use super::value::Numeric;
+use super::value::ValueKey;
 use super::QueryResult;
+use super::WorkerError;
+
+const MAX_REPORTED_ROWS: usize = 5;
 
 #[inline]
 pub fn assert_column(row: &Row, column: &ColumnClause) -> QueryResult {
@@ -19,18 +37,168 @@ pub fn assert_column(row: &Row, column: &ColumnClause) -> QueryResult {
             ref name,
             condition,
             ref value,
-        } => assert_column_compare(row, condition, name, value),
+            nulls,
+        } => assert_column_compare(row, condition, name, value, nulls),
         ColumnClause::Range {
             ref name,
             ref from,
             ref to,
-        } => assert_column_range(row, name, from, to),
-        ColumnClause::Any { ref name, ref any } => assert_column_any(row, name, any),
+            nulls,
+        } => assert_column_range(row, name, from, to, nulls),
+        ColumnClause::Any {
+            ref name,
+            ref any,
+            nulls,
+        } => assert_column_any(row, name, any, nulls),
         ColumnClause::Contains {
             ref name,
             ref contains,
-        } => assert_column_contains(row, name, contains),
+            nulls,
+        } => assert_column_contains(row, name, contains, nulls),
+        ColumnClause::IsNull { ref name, is_null } => assert_column_is_null(row, name, is_null),
+    }
+}
+
+/// Computes aggregate function over column values of all rows and compares it with
+/// expected value, NULL values are ignored by all functions except `null_count`.
+pub fn assert_aggregate(rows: &Rows, clause: &AggregateClause) -> QueryResult {
+    let title = format!("Aggregate '{}({})'", clause.function(), clause.column());
+    let mut values = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        match ColumnValue::from_row(&row, clause.column()) {
+            Ok(ColumnValue::Null) => {}
+            Ok(value) => values.push(value),
+            Err(message) => return QueryResult::fail(format!("{} failed: {}", title, message)),
+        }
+    }
+
+    let actual = match compute_aggregate(clause.function(), values, rows.len()) {
+        Ok(ColumnValue::Null) => {
+            return QueryResult::fail(format!("{} failed: no values to aggregate", title))
+        }
+        Ok(actual) => actual,
+        Err(message) => return QueryResult::fail(format!("{} failed: {}", title, message)),
+    };
+
+    match actual.coerce(clause.value()) {
+        Ok(expected) => assert_condition(title, clause.condition(), expected, actual),
+        Err(message) => QueryResult::fail(format!("{} failed: {}", title, message)),
+    }
+}
+
+/// Checks that no two rows have equal values in given columns, NULL values are
+/// considered equal like in `GROUP BY`.
+pub fn assert_unique(rows: &Rows, names: &[String]) -> QueryResult {
+    let keys = match read_rows(rows, names) {
+        Ok(keys) => keys,
+        Err(message) => return QueryResult::fail(format!("Unique failed: {}", message)),
+    };
+
+    make_unique_result(names, keys)
+}
+
+fn make_unique_result(names: &[String], keys: Vec<Vec<ColumnValue>>) -> QueryResult {
+    let mut indexes: HashMap<Vec<ValueKey>, usize> = HashMap::with_capacity(keys.len());
+    let mut counts: Vec<(Vec<ColumnValue>, usize)> = Vec::new();
+
+    for key in keys {
+        match indexes.entry(key.iter().map(ColumnValue::key).collect()) {
+            Entry::Occupied(entry) => counts[*entry.get()].1 += 1,
+            Entry::Vacant(entry) => {
+                entry.insert(counts.len());
+                counts.push((key, 1));
+            }
+        }
+    }
+
+    let duplicates: Vec<String> = counts
+        .iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(key, count)| format!("{} x {}", format_actual_row(names, key), count))
+        .collect();
+    let mut message = format!("Unique failed: {} duplicated keys", duplicates.len());
+
+    if !duplicates.is_empty() {
+        message.push_str("; ");
+        message.push_str(&join_values(
+            &duplicates[..duplicates.len().min(MAX_REPORTED_ROWS)],
+            ", ",
+        ));
+
+        if duplicates.len() > MAX_REPORTED_ROWS {
+            message.push_str(", ...");
+        }
     }
+
+    make_query_result(duplicates.is_empty(), message)
+}
+
+fn compute_aggregate(
+    function: AggregateFunction,
+    values: Vec<ColumnValue>,
+    n_rows: usize,
+) -> Result<ColumnValue, String> {
+    let result = match function {
+        AggregateFunction::NullCount => ColumnValue::Integer((n_rows - values.len()) as i64),
+        AggregateFunction::CountDistinct => {
+            let distinct: HashSet<ValueKey> = values.iter().map(ColumnValue::key).collect();
+
+            ColumnValue::Integer(distinct.len() as i64)
+        }
+        AggregateFunction::Min => extreme_value(values, Ordering::Less),
+        AggregateFunction::Max => extreme_value(values, Ordering::Greater),
+        AggregateFunction::Sum | AggregateFunction::Avg if values.is_empty() => ColumnValue::Null,
+        AggregateFunction::Sum | AggregateFunction::Avg => {
+            // Integer and NUMERIC values are summed exactly, float values make sum float.
+            let mut integer_sum = Some(0i64);
+            let mut numeric_sum = Some(Numeric::zero());
+            let mut float_sum = 0.0;
+
+            for value in &values {
+                match *value {
+                    ColumnValue::Integer(value) => {
+                        integer_sum = integer_sum.and_then(|sum| sum.checked_add(value));
+                        numeric_sum = numeric_sum.map(|sum| sum + Numeric::from_i64(value));
+                    }
+                    ColumnValue::Numeric(ref value) => {
+                        integer_sum = None;
+                        numeric_sum = numeric_sum.map(|sum| sum + value.clone());
+                    }
+                    _ => {
+                        integer_sum = None;
+                        numeric_sum = None;
+                    }
+                }
+
+                float_sum += value
+                    .as_float()
+                    .ok_or_else(|| format!("can not sum non numeric value {}", value))?;
+            }
+
+            match (function, integer_sum, numeric_sum) {
+                (AggregateFunction::Sum, Some(sum), _) => ColumnValue::Integer(sum),
+                (AggregateFunction::Sum, None, Some(sum)) => ColumnValue::Numeric(sum),
+                (AggregateFunction::Sum, None, None) => ColumnValue::Float(float_sum),
+                (_, _, Some(sum)) => ColumnValue::Float(sum.to_f64() / values.len() as f64),
+                _ => ColumnValue::Float(float_sum / values.len() as f64),
+            }
+        }
+    };
+
+    Ok(result)
+}
+
+fn extreme_value(values: Vec<ColumnValue>, ordering: Ordering) -> ColumnValue {
+    values
+        .into_iter()
+        .fold(ColumnValue::Null, |result, value| {
+            if result.is_null() || value.partial_cmp(&result) == Some(ordering) {
+                value
+            } else {
+                result
+            }
+        })
 }
 
 #[inline]
@@ -41,19 +209,456 @@ pub fn assert_n_rows(actual_rows: usize, n_rows: &NRowsClause) -> QueryResult {
     assert_condition("N rows", condition, expected_rows, actual_rows)
 }
 
-#[inline]
-fn assert_column_compare(row: &Row, conditon: Condition, name: &str, value: &Value) -> QueryResult {
-    match *value {
-        Value::Integer(ref value) => with_row_value(row, name, |actual| {
-            assert_condition(format!("Column '{}'", name), conditon, value, actual)
-        }),
-        Value::Float(ref value) => with_row_value(row, name, |actual| {
-            assert_condition(format!("Column '{}'", name), conditon, value, actual)
-        }),
-        Value::String(ref value) => with_row_value(row, name, |actual| {
-            assert_condition(format!("Column '{}'", name), conditon, value, actual)
-        }),
+pub fn assert_error(error: WorkerError, clause: &ErrorClause) -> QueryResult {
+    let (code, message, constraint) = match error {
+        WorkerError::DatabaseError {
+            code,
+            message,
+            constraint,
+        } => (code, message, constraint),
+        error => return error.into(),
+    };
+
+    if let Some(sqlstate) = clause.sqlstate() {
+        if !code.starts_with(sqlstate.as_str()) {
+            return QueryResult::fail(format!(
+                "SQLSTATE failed: {} == {} - {}",
+                code, sqlstate, message
+            ));
+        }
+    }
+
+    if let Some(sub_string) = clause.message_contains() {
+        if !message.contains(sub_string.as_str()) {
+            return QueryResult::fail(format!(
+                "Error message failed: '{}' contains '{}'",
+                message, sub_string
+            ));
+        }
+    }
+
+    if let Some(expected) = clause.constraint() {
+        let actual = constraint.unwrap_or_else(|| "none".into());
+
+        if actual != *expected {
+            return QueryResult::fail(format!(
+                "Constraint failed: {} == {} - {}",
+                actual, expected, message
+            ));
+        }
+    }
+
+    QueryResult::success()
+}
+
+pub fn assert_rows(rows: &Rows, clause: &RowsClause) -> QueryResult {
+    let expected_rows = match clause.rows() {
+        Ok(expected_rows) => expected_rows,
+        Err(message) => return QueryResult::fail(message),
+    };
+    let mut names: Vec<String> = Vec::new();
+
+    for row in &expected_rows {
+        for &(ref name, _) in row {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    let mut actual_rows = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let mut values = Vec::with_capacity(names.len());
+
+        for name in &names {
+            match ColumnValue::from_row(&row, name) {
+                Ok(value) => values.push(value),
+                Err(message) => return QueryResult::fail(message),
+            }
+        }
+
+        actual_rows.push(values);
+    }
+
+    let expected_rows: Vec<Vec<(usize, Option<Value>)>> = expected_rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|(name, value)| (names.iter().position(|n| *n == name).unwrap(), value))
+                .collect()
+        })
+        .collect();
+
+    if clause.ordered() {
+        compare_ordered_rows(&names, &expected_rows, &actual_rows)
+    } else {
+        compare_unordered_rows(&names, &expected_rows, &actual_rows)
+    }
+}
+
+fn compare_ordered_rows(
+    names: &[String],
+    expected_rows: &[Vec<(usize, Option<Value>)>],
+    actual_rows: &[Vec<ColumnValue>],
+) -> QueryResult {
+    let mut missing = Vec::new();
+    let mut unexpected = Vec::new();
+    let mut mismatched = Vec::new();
+
+    for index in 0..expected_rows.len().max(actual_rows.len()) {
+        match (expected_rows.get(index), actual_rows.get(index)) {
+            (Some(expected), Some(actual)) => if !is_row_matches(expected, actual) {
+                mismatched.push(format!(
+                    "row {}: {} != {}",
+                    index + 1,
+                    format_actual_row(names, actual),
+                    format_expected_row(names, expected)
+                ));
+            },
+            (Some(expected), None) => missing.push(format_expected_row(names, expected)),
+            (None, Some(actual)) => unexpected.push(format_actual_row(names, actual)),
+            (None, None) => {}
+        }
+    }
+
+    make_rows_result("Rows", &missing, &unexpected, &mismatched)
+}
+
+fn compare_unordered_rows(
+    names: &[String],
+    expected_rows: &[Vec<(usize, Option<Value>)>],
+    actual_rows: &[Vec<ColumnValue>],
+) -> QueryResult {
+    let (missing, unexpected) = match_rows(expected_rows, actual_rows, |expected, actual| {
+        is_row_matches(expected, actual)
+    });
+    let missing: Vec<String> = missing
+        .into_iter()
+        .map(|index| format_expected_row(names, &expected_rows[index]))
+        .collect();
+    let unexpected: Vec<String> = unexpected
+        .into_iter()
+        .map(|index| format_actual_row(names, &actual_rows[index]))
+        .collect();
+
+    make_rows_result("Rows", &missing, &unexpected, &[])
+}
+
+/// Finds maximum matching between expected and actual rows with augmenting paths, so
+/// result does not depend on order of rows when expected row matches several actual rows.
+/// Returns indices of missing expected rows and unexpected actual rows.
+fn match_rows<E, A, F>(
+    expected_rows: &[E],
+    actual_rows: &[A],
+    is_match: F,
+) -> (Vec<usize>, Vec<usize>)
+where
+    F: Fn(&E, &A) -> bool,
+{
+    let candidates: Vec<Vec<usize>> = expected_rows
+        .iter()
+        .map(|expected| {
+            (0..actual_rows.len())
+                .filter(|&index| is_match(expected, &actual_rows[index]))
+                .collect()
+        })
+        .collect();
+    let mut matched_by: Vec<Option<usize>> = vec![None; actual_rows.len()];
+    let mut missing = Vec::new();
+
+    for expected_index in 0..expected_rows.len() {
+        let mut is_visited = vec![false; actual_rows.len()];
+
+        if !augment(expected_index, &candidates, &mut matched_by, &mut is_visited) {
+            missing.push(expected_index);
+        }
     }
+
+    let unexpected = matched_by
+        .iter()
+        .enumerate()
+        .filter(|&(_, matched_by)| matched_by.is_none())
+        .map(|(index, _)| index)
+        .collect();
+
+    (missing, unexpected)
+}
+
+fn augment(
+    expected_index: usize,
+    candidates: &[Vec<usize>],
+    matched_by: &mut [Option<usize>],
+    is_visited: &mut [bool],
+) -> bool {
+    for &actual_index in &candidates[expected_index] {
+        if is_visited[actual_index] {
+            continue;
+        }
+
+        is_visited[actual_index] = true;
+
+        let is_free = match matched_by[actual_index] {
+            Some(other_index) => augment(other_index, candidates, matched_by, is_visited),
+            None => true,
+        };
+
+        if is_free {
+            matched_by[actual_index] = Some(expected_index);
+            return true;
+        }
+    }
+
+    false
+}
+
+fn is_row_matches(expected: &[(usize, Option<Value>)], actual: &[ColumnValue]) -> bool {
+    expected.iter().all(|&(index, ref value)| {
+        let actual = &actual[index];
+
+        match *value {
+            None => actual.is_null(),
+            Some(ref value) => !actual.is_null() && actual.coerce(value).ok().as_ref() == Some(actual),
+        }
+    })
+}
+
+fn format_expected_row(names: &[String], row: &[(usize, Option<Value>)]) -> String {
+    let values: Vec<String> = row.iter()
+        .map(|&(index, ref value)| match *value {
+            Some(ref value) => format!("{}: {}", names[index], value),
+            None => format!("{}: NULL", names[index]),
+        })
+        .collect();
+
+    format!("{{ {} }}", values.join(", "))
+}
+
+fn format_actual_row(names: &[String], row: &[ColumnValue]) -> String {
+    let values: Vec<String> = names
+        .iter()
+        .zip(row)
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+
+    format!("{{ {} }}", values.join(", "))
+}
+
+fn make_rows_result(
+    title: &str,
+    missing: &[String],
+    unexpected: &[String],
+    mismatched: &[String],
+) -> QueryResult {
+    let mut message = format!(
+        "{} failed: {} missing, {} unexpected, {} mismatched",
+        title,
+        missing.len(),
+        unexpected.len(),
+        mismatched.len()
+    );
+
+    for &(title, rows) in &[
+        ("missing", missing),
+        ("unexpected", unexpected),
+        ("mismatched", mismatched),
+    ] {
+        if !rows.is_empty() {
+            message.push_str(&format!("; {} ", title));
+            message.push_str(&join_values(&rows[..rows.len().min(MAX_REPORTED_ROWS)], ", "));
+
+            if rows.len() > MAX_REPORTED_ROWS {
+                message.push_str(", ...");
+            }
+        }
+    }
+
+    make_query_result(
+        missing.is_empty() && unexpected.is_empty() && mismatched.is_empty(),
+        message,
+    )
+}
+
+/// Compares query result with result of the same query on other target, missing rows
+/// are found only on other target, unexpected rows only on this one.
+pub fn assert_compare(rows: &Rows, peer_rows: &Rows, clause: &CompareClause) -> QueryResult {
+    let title = format!("Compare with {}", clause.with());
+    let names: Vec<String> = match clause.columns() {
+        Some(columns) => columns.clone(),
+        None => column_names(rows),
+    };
+    let actual_rows = match read_rows(rows, &names) {
+        Ok(actual_rows) => actual_rows,
+        Err(message) => return QueryResult::fail(format!("{} failed: {}", title, message)),
+    };
+    let expected_rows = match read_rows(peer_rows, &names) {
+        Ok(expected_rows) => expected_rows,
+        Err(message) => {
+            return QueryResult::fail(format!("{} failed: {} on {}", title, message, clause.with()))
+        }
+    };
+    let (missing, unexpected, mismatched) = diff_rows(
+        &names,
+        &expected_rows,
+        &actual_rows,
+        clause.ordered(),
+        clause.tolerance(),
+    );
+
+    make_rows_result(&title, &missing, &unexpected, &mismatched)
+}
+
+/// Compares query result with result of compare query. Single values are compared with
+/// the condition, other results must be equal (or differ for `!=`) row by row.
+pub fn assert_compare_with(
+    rows: &Rows,
+    other_rows: &Rows,
+    clause: &CompareWithClause,
+) -> QueryResult {
+    let title = "Compare query";
+    let names = column_names(rows);
+    let other_names = column_names(other_rows);
+
+    if names.len() != other_names.len() {
+        return QueryResult::fail(format!(
+            "{} failed: {} columns != {} columns",
+            title,
+            names.len(),
+            other_names.len()
+        ));
+    }
+
+    let actual_rows = match read_rows(rows, &names) {
+        Ok(actual_rows) => actual_rows,
+        Err(message) => return QueryResult::fail(format!("{} failed: {}", title, message)),
+    };
+    let expected_rows = match read_rows(other_rows, &other_names) {
+        Ok(expected_rows) => expected_rows,
+        Err(message) => return QueryResult::fail(format!("{} failed: {}", title, message)),
+    };
+
+    if names.len() == 1 && actual_rows.len() == 1 && expected_rows.len() == 1 {
+        return assert_condition(
+            title,
+            clause.condition(),
+            &expected_rows[0][0],
+            &actual_rows[0][0],
+        );
+    }
+
+    let (missing, unexpected, mismatched) =
+        diff_rows(&names, &expected_rows, &actual_rows, clause.ordered(), None);
+
+    match clause.condition() {
+        Condition::Equal => make_rows_result(title, &missing, &unexpected, &mismatched),
+        Condition::NotEqual => make_query_result(
+            !missing.is_empty() || !unexpected.is_empty() || !mismatched.is_empty(),
+            format!("{} failed: results are equal", title),
+        ),
+        _ => QueryResult::fail(format!(
+            "{} failed: condition requires single value, got {} rows and {} rows",
+            title,
+            actual_rows.len(),
+            expected_rows.len()
+        )),
+    }
+}
+
+/// Finds rows missing in actual result, unexpected in it and, for ordered comparison,
+/// mismatched at the same position.
+fn diff_rows(
+    names: &[String],
+    expected_rows: &[Vec<ColumnValue>],
+    actual_rows: &[Vec<ColumnValue>],
+    ordered: bool,
+    tolerance: Option<f64>,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let mut missing = Vec::new();
+    let mut unexpected = Vec::new();
+    let mut mismatched = Vec::new();
+
+    if ordered {
+        for index in 0..expected_rows.len().max(actual_rows.len()) {
+            match (expected_rows.get(index), actual_rows.get(index)) {
+                (Some(expected), Some(actual)) => if !is_values_match(expected, actual, tolerance) {
+                    mismatched.push(format!(
+                        "row {}: {} != {}",
+                        index + 1,
+                        format_actual_row(names, actual),
+                        format_actual_row(names, expected)
+                    ));
+                },
+                (Some(expected), None) => missing.push(format_actual_row(names, expected)),
+                (None, Some(actual)) => unexpected.push(format_actual_row(names, actual)),
+                (None, None) => {}
+            }
+        }
+    } else {
+        let (missing_indices, unexpected_indices) =
+            match_rows(expected_rows, actual_rows, |expected, actual| {
+                is_values_match(expected, actual, tolerance)
+            });
+
+        for index in missing_indices {
+            missing.push(format_actual_row(names, &expected_rows[index]));
+        }
+
+        for index in unexpected_indices {
+            unexpected.push(format_actual_row(names, &actual_rows[index]));
+        }
+    }
+
+    (missing, unexpected, mismatched)
+}
+
+fn column_names(rows: &Rows) -> Vec<String> {
+    rows.columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect()
+}
+
+fn read_rows(rows: &Rows, names: &[String]) -> Result<Vec<Vec<ColumnValue>>, String> {
+    let mut result = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let mut values = Vec::with_capacity(names.len());
+
+        for name in names {
+            values.push(ColumnValue::from_row(&row, name)?);
+        }
+
+        result.push(values);
+    }
+
+    Ok(result)
+}
+
+fn is_values_match(expected: &[ColumnValue], actual: &[ColumnValue], tolerance: Option<f64>) -> bool {
+    expected
+        .iter()
+        .zip(actual)
+        .all(|(expected, actual)| match (tolerance, expected.as_float(), actual.as_float()) {
+            (Some(tolerance), Some(expected), Some(actual)) => {
+                (expected - actual).abs() <= tolerance
+            }
+            _ => expected == actual,
+        })
+}
+
+#[inline]
+fn assert_column_compare(
+    row: &Row,
+    conditon: Condition,
+    name: &str,
+    value: &Value,
+    nulls: NullPolicy,
+) -> QueryResult {
+    with_row_value(row, name, nulls, |actual| {
+        with_coerced_value(name, actual, value, |expected| {
+            assert_condition(format!("Column '{}'", name), conditon, expected, actual)
+        })
+    })
 }
 
 #[inline]
@@ -91,19 +696,18 @@ where
 }
 
 #[inline]
-fn assert_column_range(row: &Row, name: &str, from: &Value, to: &Value) -> QueryResult {
-    match (from, to) {
-        (&Value::Integer(ref from), &Value::Integer(ref to)) => {
-            with_row_value(row, name, |actual| assert_range(name, from, to, actual))
-        }
-        (&Value::Float(ref from), &Value::Float(ref to)) => {
-            with_row_value(row, name, |actual| assert_range(name, from, to, actual))
-        }
-        (&Value::String(ref from), &Value::String(ref to)) => {
-            with_row_value(row, name, |actual| assert_range(name, from, to, actual))
-        }
-        _ => QueryResult::fail("Parameters 'from' and 'to' have incompatible types"),
-    }
+fn assert_column_range(
+    row: &Row,
+    name: &str,
+    from: &Value,
+    to: &Value,
+    nulls: NullPolicy,
+) -> QueryResult {
+    with_row_value(row, name, nulls, |actual| {
+        with_coerced_value(name, actual, from, |from| {
+            with_coerced_value(name, actual, to, |to| assert_range(name, from, to, actual))
+        })
+    })
 }
 
 #[inline]
@@ -122,18 +726,19 @@ where
 }
 
 #[inline]
-fn assert_column_any(row: &Row, name: &str, values: &Values) -> QueryResult {
-    match *values {
-        Values::Integer(ref values) => {
-            with_row_value(row, name, |actual| assert_any(name, values, actual))
-        }
-        Values::Float(ref values) => {
-            with_row_value(row, name, |actual| assert_any(name, values, actual))
-        }
-        Values::String(ref values) => {
-            with_row_value(row, name, |actual| assert_any(name, values, actual))
+fn assert_column_any(row: &Row, name: &str, values: &[Value], nulls: NullPolicy) -> QueryResult {
+    with_row_value(row, name, nulls, |actual| {
+        let mut expected = Vec::with_capacity(values.len());
+
+        for value in values {
+            match actual.coerce(value) {
+                Ok(value) => expected.push(value),
+                Err(message) => return QueryResult::fail(format!("Column '{}' failed: {}", name, message)),
+            }
         }
-    }
+
+        assert_any(name, &expected, actual)
+    })
 }
 
 #[inline]
@@ -174,8 +779,15 @@ where
 }
 
 #[inline]
-fn assert_column_contains(row: &Row, name: &str, sub_string: &String) -> QueryResult {
-    with_row_value(row, name, |value| assert_contains(name, value, sub_string))
+fn assert_column_contains(
+    row: &Row,
+    name: &str,
+    sub_string: &String,
+    nulls: NullPolicy,
+) -> QueryResult {
+    with_row_value(row, name, nulls, |value| {
+        assert_contains(name, &format!("{}", value), sub_string)
+    })
 }
 
 #[inline]
@@ -193,17 +805,56 @@ where
 }
 
 #[inline]
-fn with_row_value<F, T>(row: &Row, name: &str, callback: F) -> QueryResult
+fn assert_column_is_null(row: &Row, name: &str, is_null: bool) -> QueryResult {
+    match ColumnValue::from_row(row, name) {
+        Ok(ref value) => assert_is_null(name, is_null, value.is_null()),
+        Err(message) => QueryResult::fail(message),
+    }
+}
+
+#[inline]
+fn assert_is_null<S>(name: S, expected: bool, actual: bool) -> QueryResult
 where
-    F: FnOnce(&T) -> QueryResult,
-    T: FromSql + Display,
+    S: Display,
 {
-    let actual_value: Option<PgResult<T>> = row.get_opt(name);
+    if expected {
+        make_query_result(actual, format!("Column '{}' failed: value is not NULL", name))
+    } else {
+        make_query_result(!actual, format!("Column '{}' failed: value is NULL", name))
+    }
+}
 
-    match actual_value {
-        None => QueryResult::fail(format!("Column {} does not exists", name)),
-        Some(Err(err)) => QueryResult::fail(format!("Failed to get {} value - {}", name, err)),
-        Some(Ok(ref actual_value)) => callback(actual_value),
+#[inline]
+fn assert_null_policy<S>(name: S, nulls: NullPolicy) -> QueryResult
+where
+    S: Display,
+{
+    make_query_result(
+        nulls == NullPolicy::Allow,
+        format!("Column '{}' failed: NULL is not allowed", name),
+    )
+}
+
+#[inline]
+fn with_row_value<F>(row: &Row, name: &str, nulls: NullPolicy, callback: F) -> QueryResult
+where
+    F: FnOnce(&ColumnValue) -> QueryResult,
+{
+    match ColumnValue::from_row(row, name) {
+        Err(message) => QueryResult::fail(message),
+        Ok(ColumnValue::Null) => assert_null_policy(name, nulls),
+        Ok(ref actual_value) => callback(actual_value),
+    }
+}
+
+#[inline]
+fn with_coerced_value<F>(name: &str, actual: &ColumnValue, value: &Value, callback: F) -> QueryResult
+where
+    F: FnOnce(&ColumnValue) -> QueryResult,
+{
+    match actual.coerce(value) {
+        Ok(ref expected_value) => callback(expected_value),
+        Err(message) => QueryResult::fail(format!("Column '{}' failed: {}", name, message)),
     }
 }
 
@@ -219,14 +870,71 @@ where
     }
 }
 
+pub fn assert_duration(actual: Duration, max_duration: Duration) -> QueryResult {
+    if actual <= max_duration {
+        QueryResult::success()
+    } else {
+        QueryResult::fail(format!(
+            "Duration failed: {} ms <= {} ms",
+            as_millis(actual),
+            as_millis(max_duration)
+        ))
+    }
+}
+
+pub fn as_millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + u64::from(duration.subsec_millis())
+}
+
+pub fn bind_parameters(param_types: &[Type], case: &TestCase) -> Result<Vec<ColumnValue>, String> {
+    let bind = case.bind();
+
+    if bind.len() != param_types.len() {
+        return Err(format!(
+            "Query expects {} parameters, but {} bound",
+            param_types.len(),
+            bind.len()
+        ));
+    }
+
+    let mut result = Vec::with_capacity(bind.len());
+
+    for (index, (name, param_type)) in bind.iter().zip(param_types).enumerate() {
+        let value = case.variables()
+            .get(name)
+            .ok_or_else(|| format!("Undefined bind variable '{}'", name))?;
+        let parameter = ColumnValue::from_value(param_type, value)
+            .map_err(|message| format!("Parameter ${} failed: {}", index + 1, message))?;
+
+        result.push(parameter);
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod test {
     use serde_yaml;
 
+    use dto::AggregateFunction;
     use dto::NRowsClause;
+    use dto::NullPolicy;
+    use dto::Value;
 
+    use super::assert_error;
+    use super::assert_is_null;
     use super::assert_n_rows;
+    use super::assert_null_policy;
+    use super::compare_ordered_rows;
+    use super::compare_unordered_rows;
+    use super::compute_aggregate;
+    use super::diff_rows;
+    use super::is_values_match;
+    use super::make_unique_result;
+    use super::ColumnValue;
+    use super::Numeric;
     use super::QueryResult;
+    use super::WorkerError;
 
     #[test]
     fn n_rows_success_if_actual_eq_expected() {
@@ -299,4 +1007,210 @@ mod test {
         assert_eq!(QueryResult::success(), assert_3_rows);
         assert_eq!(QueryResult::success(), assert_4_rows);
     }
+
+    #[test]
+    fn is_null_success_if_actual_matches_expected() {
+        assert_eq!(QueryResult::success(), assert_is_null("name", true, true));
+        assert_eq!(
+            QueryResult::fail("Column 'name' failed: value is not NULL"),
+            assert_is_null("name", true, false)
+        );
+        assert_eq!(
+            QueryResult::fail("Column 'name' failed: value is NULL"),
+            assert_is_null("name", false, true)
+        );
+        assert_eq!(QueryResult::success(), assert_is_null("name", false, false));
+    }
+
+    #[test]
+    fn null_success_only_if_allowed() {
+        assert_eq!(
+            QueryResult::success(),
+            assert_null_policy("name", NullPolicy::Allow)
+        );
+        assert_eq!(
+            QueryResult::fail("Column 'name' failed: NULL is not allowed"),
+            assert_null_policy("name", NullPolicy::Deny)
+        );
+    }
+
+    #[test]
+    fn unordered_rows_report_missing_and_unexpected() {
+        let names = vec!["id".into()];
+        let expected = vec![
+            vec![(0, Some(Value::Integer(1)))],
+            vec![(0, Some(Value::Integer(2)))],
+        ];
+        let actual = vec![vec![ColumnValue::Integer(3)], vec![ColumnValue::Integer(1)]];
+
+        assert_eq!(
+            QueryResult::fail(
+                "Rows failed: 1 missing, 1 unexpected, 0 mismatched; \
+                 missing { id: 2 }; unexpected { id: 3 }"
+            ),
+            compare_unordered_rows(&names, &expected, &actual)
+        );
+        assert_eq!(
+            QueryResult::success(),
+            compare_unordered_rows(&names, &expected[..1], &actual[1..])
+        );
+    }
+
+    #[test]
+    fn unordered_rows_match_overlapping_partial_rows() {
+        let names = vec!["id".into(), "kind".into()];
+        let expected = vec![
+            vec![(0, Some(Value::Integer(1)))],
+            vec![(0, Some(Value::Integer(1))), (1, Some(Value::Integer(2)))],
+        ];
+        let actual = vec![
+            vec![ColumnValue::Integer(1), ColumnValue::Integer(2)],
+            vec![ColumnValue::Integer(1), ColumnValue::Integer(3)],
+        ];
+
+        assert_eq!(
+            QueryResult::success(),
+            compare_unordered_rows(&names, &expected, &actual)
+        );
+
+        let names = vec!["price".into()];
+        let expected = vec![vec![ColumnValue::Float(1.2)], vec![ColumnValue::Float(1.0)]];
+        let actual = vec![vec![ColumnValue::Float(1.1)], vec![ColumnValue::Float(1.25)]];
+        let (missing, unexpected, _) = diff_rows(&names, &expected, &actual, false, Some(0.15));
+
+        assert!(missing.is_empty() && unexpected.is_empty());
+    }
+
+    #[test]
+    fn ordered_rows_report_mismatched() {
+        let names = vec!["id".into()];
+        let expected = vec![vec![(0, Some(Value::Integer(1)))], vec![(0, None)]];
+        let actual = vec![vec![ColumnValue::Null], vec![ColumnValue::Null]];
+
+        assert_eq!(
+            QueryResult::fail(
+                "Rows failed: 0 missing, 0 unexpected, 1 mismatched; \
+                 mismatched row 1: { id: NULL } != { id: 1 }"
+            ),
+            compare_ordered_rows(&names, &expected, &actual)
+        );
+    }
+
+    #[test]
+    fn diff_rows_ignores_order_unless_ordered() {
+        let names = vec!["id".into()];
+        let expected = vec![vec![ColumnValue::Integer(1)], vec![ColumnValue::Integer(2)]];
+        let actual = vec![vec![ColumnValue::Integer(2)], vec![ColumnValue::Integer(1)]];
+        let (missing, unexpected, mismatched) = diff_rows(&names, &expected, &actual, false, None);
+
+        assert!(missing.is_empty() && unexpected.is_empty() && mismatched.is_empty());
+
+        let (missing, unexpected, mismatched) = diff_rows(&names, &expected, &actual, true, None);
+
+        assert!(missing.is_empty() && unexpected.is_empty());
+        assert_eq!(
+            vec!["row 1: { id: 2 } != { id: 1 }", "row 2: { id: 1 } != { id: 2 }"],
+            mismatched
+        );
+    }
+
+    #[test]
+    fn aggregates_ignore_nulls() {
+        let values = vec![
+            ColumnValue::Integer(3),
+            ColumnValue::Integer(1),
+            ColumnValue::Integer(3),
+        ];
+        let aggregate = |function| compute_aggregate(function, values.clone(), 5).unwrap();
+
+        assert_eq!(ColumnValue::Integer(1), aggregate(AggregateFunction::Min));
+        assert_eq!(ColumnValue::Integer(3), aggregate(AggregateFunction::Max));
+        assert_eq!(ColumnValue::Integer(7), aggregate(AggregateFunction::Sum));
+        assert_eq!(ColumnValue::Float(7.0 / 3.0), aggregate(AggregateFunction::Avg));
+        assert_eq!(ColumnValue::Integer(2), aggregate(AggregateFunction::CountDistinct));
+        assert_eq!(ColumnValue::Integer(2), aggregate(AggregateFunction::NullCount));
+        assert!(compute_aggregate(AggregateFunction::Max, Vec::new(), 1).unwrap().is_null());
+        assert!(
+            compute_aggregate(AggregateFunction::Sum, vec![ColumnValue::Text("a".into())], 1)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn numeric_sum_is_exact() {
+        let numeric = |value| ColumnValue::Numeric(Numeric::parse(value).unwrap());
+        let values = vec![numeric("0.10"), numeric("0.20"), ColumnValue::Integer(1)];
+        let sum = compute_aggregate(AggregateFunction::Sum, values, 3).unwrap();
+
+        assert_eq!(numeric("1.3"), sum);
+        assert_eq!(Ok(numeric("1.3")), sum.coerce(&Value::Float(1.3)));
+
+        let values = vec![numeric("12345678901234567.89"), numeric("0.01")];
+
+        assert_eq!(
+            numeric("12345678901234567.9"),
+            compute_aggregate(AggregateFunction::Sum, values, 2).unwrap()
+        );
+
+        let values = vec![numeric("0.5"), ColumnValue::Float(0.25)];
+
+        assert_eq!(
+            ColumnValue::Float(0.75),
+            compute_aggregate(AggregateFunction::Sum, values, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn unique_reports_duplicated_keys() {
+        let names = vec!["id".into()];
+        let keys = vec![
+            vec![ColumnValue::Integer(1)],
+            vec![ColumnValue::Null],
+            vec![ColumnValue::Integer(1)],
+            vec![ColumnValue::Text("NULL".into())],
+            vec![ColumnValue::Null],
+            vec![ColumnValue::Integer(1)],
+        ];
+        let floats = vec![vec![ColumnValue::Float(0.0)], vec![ColumnValue::Float(-0.0)]];
+
+        assert_eq!(
+            QueryResult::fail("Unique failed: 2 duplicated keys; { id: 1 } x 3, { id: NULL } x 2"),
+            make_unique_result(&names, keys.clone())
+        );
+        assert_eq!(
+            QueryResult::fail("Unique failed: 1 duplicated keys; { id: 0 } x 2"),
+            make_unique_result(&names, floats)
+        );
+        assert_eq!(QueryResult::success(), make_unique_result(&names, keys[..2].to_vec()));
+    }
+
+    #[test]
+    fn values_match_within_tolerance() {
+        let expected = vec![ColumnValue::Text("a".into()), ColumnValue::Float(10.0)];
+        let actual = vec![ColumnValue::Text("a".into()), ColumnValue::Integer(10)];
+        let close = vec![ColumnValue::Text("a".into()), ColumnValue::Float(10.004)];
+
+        assert!(is_values_match(&expected, &actual, None));
+        assert!(!is_values_match(&expected, &close, None));
+        assert!(is_values_match(&expected, &close, Some(0.01)));
+        assert!(!is_values_match(&expected, &close, Some(0.001)));
+    }
+
+    #[test]
+    fn error_success_if_all_fields_match() {
+        let clause = serde_yaml::from_str(
+            "{ sqlstate: '23', message_contains: duplicate, constraint: users_pkey }",
+        ).unwrap();
+        let error = |code: &str| WorkerError::DatabaseError {
+            code: code.into(),
+            message: "duplicate key value".into(),
+            constraint: Some("users_pkey".into()),
+        };
+
+        assert_eq!(QueryResult::success(), assert_error(error("23505"), &clause));
+        assert_eq!(
+            QueryResult::fail("SQLSTATE failed: 42601 == 23 - duplicate key value"),
+            assert_error(error("42601"), &clause)
+        );
+    }
 }
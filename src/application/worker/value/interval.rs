@@ -0,0 +1,298 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use chrono::Duration;
+use postgres::types::FromSql;
+use postgres::types::IsNull;
+use postgres::types::ToSql;
+use postgres::types::Type;
+use postgres::types::INTERVAL;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+const DAYS_PER_MONTH: i64 = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    microseconds: i64,
+}
+
+impl Interval {
+    pub fn parse(value: &str) -> Option<Interval> {
+        let mut result = Interval::zero();
+        let mut tokens = value.split_whitespace();
+        let mut is_empty = true;
+
+        while let Some(token) = tokens.next() {
+            if token.contains(':') {
+                result.microseconds += parse_time(token)?;
+            } else {
+                let amount: f64 = token.parse().ok()?;
+                let unit = tokens.next()?;
+
+                result.add(amount, unit)?;
+            }
+
+            is_empty = false;
+        }
+
+        if is_empty {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    pub fn zero() -> Interval {
+        Interval {
+            months: 0,
+            days: 0,
+            microseconds: 0,
+        }
+    }
+
+    /// Adds amount of unit, fractional part is carried into smaller units like
+    /// PostgreSQL does: years are rounded to months, months are carried into days
+    /// and days into time.
+    fn add(&mut self, amount: f64, unit: &str) -> Option<()> {
+        match unit.to_lowercase().as_str() {
+            "year" | "years" => self.months += (amount * 12.0).round() as i32,
+            "mon" | "mons" | "month" | "months" => self.add_months(amount),
+            "week" | "weeks" => self.add_days(amount * 7.0),
+            "day" | "days" => self.add_days(amount),
+            "hour" | "hours" => self.microseconds += (amount * MICROS_PER_HOUR as f64) as i64,
+            "min" | "mins" | "minute" | "minutes" => {
+                self.microseconds += (amount * MICROS_PER_MINUTE as f64) as i64
+            }
+            "sec" | "secs" | "second" | "seconds" => {
+                self.microseconds += (amount * MICROS_PER_SECOND as f64) as i64
+            }
+            "ms" | "millisecond" | "milliseconds" => {
+                self.microseconds += (amount * 1000.0) as i64
+            }
+            "us" | "microsecond" | "microseconds" => self.microseconds += amount as i64,
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    fn add_months(&mut self, amount: f64) {
+        let months = amount.trunc();
+
+        self.months += months as i32;
+        self.add_days((amount - months) * DAYS_PER_MONTH as f64);
+    }
+
+    fn add_days(&mut self, amount: f64) {
+        let days = amount.trunc();
+
+        self.days += days as i32;
+        self.microseconds += ((amount - days) * MICROS_PER_DAY as f64).round() as i64;
+    }
+
+    pub fn to_duration(self) -> Duration {
+        Duration::microseconds(self.total_microseconds())
+    }
+
+    pub fn total_microseconds(&self) -> i64 {
+        (i64::from(self.months) * DAYS_PER_MONTH + i64::from(self.days)) * MICROS_PER_DAY
+            + self.microseconds
+    }
+}
+
+fn parse_time(value: &str) -> Option<i64> {
+    let (sign, value) = if value.starts_with('-') {
+        (-1, &value[1..])
+    } else {
+        (1, value)
+    };
+    let mut parts = value.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = match parts.next() {
+        Some(seconds) => seconds.parse().ok()?,
+        None => 0.0,
+    };
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let microseconds = hours * MICROS_PER_HOUR + minutes * MICROS_PER_MINUTE
+        + (seconds * MICROS_PER_SECOND as f64) as i64;
+
+    Some(sign * microseconds)
+}
+
+impl FromSql for Interval {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Interval, Box<Error + Sync + Send>> {
+        if raw.len() != 16 {
+            return Err("invalid interval length".into());
+        }
+
+        let mut microseconds = 0;
+
+        for byte in &raw[0..8] {
+            microseconds = (microseconds << 8) | i64::from(*byte);
+        }
+
+        let mut days = 0;
+
+        for byte in &raw[8..12] {
+            days = (days << 8) | i32::from(*byte);
+        }
+
+        let mut months = 0;
+
+        for byte in &raw[12..16] {
+            months = (months << 8) | i32::from(*byte);
+        }
+
+        Ok(Interval {
+            months,
+            days,
+            microseconds,
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == INTERVAL
+    }
+}
+
+impl ToSql for Interval {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<Error + Sync + Send>> {
+        for shift in (0..8).rev() {
+            out.push((self.microseconds >> (shift * 8)) as u8);
+        }
+
+        for shift in (0..4).rev() {
+            out.push((self.days >> (shift * 8)) as u8);
+        }
+
+        for shift in (0..4).rev() {
+            out.push((self.months >> (shift * 8)) as u8);
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == INTERVAL
+    }
+
+    to_sql_checked!();
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Interval) -> bool {
+        self.total_microseconds() == other.total_microseconds()
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Interval) -> Option<Ordering> {
+        self.total_microseconds()
+            .partial_cmp(&other.total_microseconds())
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let years = self.months / 12;
+        let months = self.months % 12;
+        let mut parts = Vec::new();
+
+        if years != 0 {
+            parts.push(format!("{} years", years));
+        }
+
+        if months != 0 {
+            parts.push(format!("{} mons", months));
+        }
+
+        if self.days != 0 {
+            parts.push(format!("{} days", self.days));
+        }
+
+        if self.microseconds != 0 || parts.is_empty() {
+            let sign = if self.microseconds < 0 { "-" } else { "" };
+            let microseconds = self.microseconds.abs();
+            let hours = microseconds / MICROS_PER_HOUR;
+            let minutes = microseconds % MICROS_PER_HOUR / MICROS_PER_MINUTE;
+            let seconds = microseconds % MICROS_PER_MINUTE / MICROS_PER_SECOND;
+            let fraction = microseconds % MICROS_PER_SECOND;
+
+            if fraction != 0 {
+                parts.push(format!(
+                    "{}{:02}:{:02}:{:02}.{:06}",
+                    sign, hours, minutes, seconds, fraction
+                ));
+            } else {
+                parts.push(format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds));
+            }
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Interval;
+
+    #[test]
+    fn parse_units_and_time() {
+        let interval = Interval::parse("1 year 2 mons 3 days 04:05:06").unwrap();
+
+        assert_eq!(14, interval.months);
+        assert_eq!(3, interval.days);
+        assert_eq!(((4 * 60 + 5) * 60 + 6) * 1_000_000, interval.microseconds);
+    }
+
+    #[test]
+    fn parse_carries_fractional_units() {
+        let hours = Interval::parse("1.5 hours").unwrap();
+        let months = Interval::parse("1.5 months").unwrap();
+        let weeks = Interval::parse("1.5 weeks").unwrap();
+
+        assert_eq!(90 * 60 * 1_000_000, hours.microseconds);
+        assert_eq!((1, 15, 0), (months.months, months.days, months.microseconds));
+        assert_eq!(
+            (0, 10, 12 * 60 * 60 * 1_000_000),
+            (weeks.months, weeks.days, weeks.microseconds)
+        );
+        assert_eq!(18, Interval::parse("1.5 years").unwrap().months);
+    }
+
+    #[test]
+    fn parse_fails_on_unknown_unit() {
+        assert!(Interval::parse("1 fortnight").is_none());
+        assert!(Interval::parse("").is_none());
+    }
+
+    #[test]
+    fn compare_month_as_30_days() {
+        let month = Interval::parse("1 month").unwrap();
+        let days = Interval::parse("30 days").unwrap();
+        let hours = Interval::parse("721 hours").unwrap();
+
+        assert!(month == days);
+        assert!(month < hours);
+    }
+
+    #[test]
+    fn display_like_postgres() {
+        let interval = Interval::parse("1 day -01:30:00").unwrap();
+
+        assert_eq!("1 days -01:30:00", format!("{}", interval));
+    }
+}
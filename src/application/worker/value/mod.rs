@@ -0,0 +1,482 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::ops::Add;
+use std::ops::Sub;
+
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::TimeZone;
+use chrono::Utc;
+use postgres::Result as PgResult;
+use postgres::rows::Row;
+use postgres::types::FromSql;
+use postgres::types::IsNull;
+use postgres::types::ToSql;
+use postgres::types::Type;
+use postgres::types::BOOL;
+use postgres::types::BPCHAR;
+use postgres::types::DATE;
+use postgres::types::FLOAT4;
+use postgres::types::FLOAT8;
+use postgres::types::INT2;
+use postgres::types::INT4;
+use postgres::types::INT8;
+use postgres::types::INTERVAL;
+use postgres::types::JSON;
+use postgres::types::JSONB;
+use postgres::types::NAME;
+use postgres::types::NUMERIC;
+use postgres::types::OID;
+use postgres::types::TEXT;
+use postgres::types::TIME;
+use postgres::types::TIMESTAMP;
+use postgres::types::TIMESTAMPTZ;
+use postgres::types::UNKNOWN;
+use postgres::types::UUID;
+use postgres::types::VARCHAR;
+use serde_json::Value as JsonValue;
+use serde_json;
+use uuid::Uuid;
+
+use dto::Value;
+
+mod interval;
+mod numeric;
+
+use self::interval::Interval;
+pub use self::numeric::Numeric;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMATS: &[&str] = &["%H:%M:%S%.f", "%H:%M"];
+const TIMESTAMP_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+const TIMESTAMPTZ_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"];
+
+#[derive(Debug, Clone)]
+pub enum ColumnValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Numeric(Numeric),
+    Boolean(bool),
+    Text(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<Utc>),
+    Interval(Interval),
+    Uuid(Uuid),
+    Json(JsonValue),
+}
+
+/// Hashable form of column value, values of the same type have equal keys if they
+/// are equal. Unlike comparison, NaN keys are equal to each other.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum ValueKey {
+    Null,
+    Integer(i64),
+    Float(u64),
+    Numeric(Numeric),
+    Boolean(bool),
+    Text(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<Utc>),
+    Interval(i64),
+    Uuid(Uuid),
+    Json(String),
+}
+
+impl ColumnValue {
+    pub fn from_row(row: &Row, name: &str) -> Result<ColumnValue, String> {
+        let column_type = match row.columns().iter().find(|column| column.name() == name) {
+            Some(column) => column.type_().clone(),
+            None => return Err(format!("Column {} does not exists", name)),
+        };
+
+        match column_type {
+            INT2 => read_value(row, name, |value: i16| ColumnValue::Integer(i64::from(value))),
+            INT4 => read_value(row, name, |value: i32| ColumnValue::Integer(i64::from(value))),
+            INT8 => read_value(row, name, ColumnValue::Integer),
+            OID => read_value(row, name, |value: u32| ColumnValue::Integer(i64::from(value))),
+            FLOAT4 => read_value(row, name, |value: f32| ColumnValue::Float(f64::from(value))),
+            FLOAT8 => read_value(row, name, ColumnValue::Float),
+            NUMERIC => read_value(row, name, ColumnValue::Numeric),
+            BOOL => read_value(row, name, ColumnValue::Boolean),
+            TEXT | VARCHAR | BPCHAR | NAME | UNKNOWN => read_value(row, name, ColumnValue::Text),
+            DATE => read_value(row, name, ColumnValue::Date),
+            TIME => read_value(row, name, ColumnValue::Time),
+            TIMESTAMP => read_value(row, name, ColumnValue::Timestamp),
+            TIMESTAMPTZ => read_value(row, name, ColumnValue::TimestampTz),
+            INTERVAL => read_value(row, name, ColumnValue::Interval),
+            UUID => read_value(row, name, ColumnValue::Uuid),
+            JSON | JSONB => read_value(row, name, ColumnValue::Json),
+            ref column_type if column_type.name() == "citext" => {
+                read_value(row, name, ColumnValue::Text)
+            }
+            ref column_type => Err(format!(
+                "Column {} has unsupported type {}",
+                name,
+                column_type.name()
+            )),
+        }
+    }
+
+    pub fn from_value(column_type: &Type, value: &Value) -> Result<ColumnValue, String> {
+        let prototype = match *column_type {
+            INT2 | INT4 | INT8 | OID => ColumnValue::Integer(0),
+            FLOAT4 | FLOAT8 => ColumnValue::Float(0.0),
+            NUMERIC => ColumnValue::Numeric(Numeric::zero()),
+            BOOL => ColumnValue::Boolean(false),
+            TEXT | VARCHAR | BPCHAR | NAME | UNKNOWN => ColumnValue::Text(String::default()),
+            DATE => ColumnValue::Date(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()),
+            TIME => ColumnValue::Time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+            TIMESTAMP => ColumnValue::Timestamp(NaiveDate::from_ymd_opt(1970, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()),
+            TIMESTAMPTZ => ColumnValue::TimestampTz(Utc.timestamp_opt(0, 0).unwrap()),
+            INTERVAL => ColumnValue::Interval(Interval::zero()),
+            UUID => ColumnValue::Uuid(Uuid::nil()),
+            JSON | JSONB => ColumnValue::Json(JsonValue::Null),
+            ref column_type if column_type.name() == "citext" => {
+                ColumnValue::Text(String::default())
+            }
+            ref column_type => return Err(format!("unsupported type {}", column_type.name())),
+        };
+
+        prototype.coerce(value)
+    }
+
+    pub fn is_null(&self) -> bool {
+        match *self {
+            ColumnValue::Null => true,
+            _ => false,
+        }
+    }
+
+    pub fn key(&self) -> ValueKey {
+        match *self {
+            ColumnValue::Null => ValueKey::Null,
+            ColumnValue::Integer(value) => ValueKey::Integer(value),
+            ColumnValue::Float(value) if value.is_nan() => ValueKey::Float(f64::NAN.to_bits()),
+            ColumnValue::Float(value) => {
+                ValueKey::Float(if value == 0.0 { 0 } else { value.to_bits() })
+            }
+            ColumnValue::Numeric(ref value) => ValueKey::Numeric(value.clone()),
+            ColumnValue::Boolean(value) => ValueKey::Boolean(value),
+            ColumnValue::Text(ref value) => ValueKey::Text(value.clone()),
+            ColumnValue::Date(value) => ValueKey::Date(value),
+            ColumnValue::Time(value) => ValueKey::Time(value),
+            ColumnValue::Timestamp(value) => ValueKey::Timestamp(value),
+            ColumnValue::TimestampTz(value) => ValueKey::TimestampTz(value),
+            ColumnValue::Interval(ref value) => ValueKey::Interval(value.total_microseconds()),
+            ColumnValue::Uuid(value) => ValueKey::Uuid(value),
+            ColumnValue::Json(ref value) => ValueKey::Json(value.to_string()),
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            ColumnValue::Integer(value) => Some(value as f64),
+            ColumnValue::Float(value) => Some(value),
+            ColumnValue::Numeric(ref value) => Some(value.to_f64()),
+            _ => None,
+        }
+    }
+
+    pub fn coerce(&self, value: &Value) -> Result<ColumnValue, String> {
+        let result = match (self, value) {
+            (&ColumnValue::Integer(_), &Value::Integer(value)) => Some(ColumnValue::Integer(value)),
+            (&ColumnValue::Integer(_), &Value::Float(value))
+            | (&ColumnValue::Float(_), &Value::Float(value)) => Some(ColumnValue::Float(value)),
+            (&ColumnValue::Float(_), &Value::Integer(value)) => {
+                Some(ColumnValue::Float(value as f64))
+            }
+            (&ColumnValue::Integer(_), &Value::String(ref value)) => value
+                .parse()
+                .map(ColumnValue::Integer)
+                .ok()
+                .or_else(|| value.parse().map(ColumnValue::Float).ok()),
+            (&ColumnValue::Float(_), &Value::String(ref value)) => {
+                value.parse().map(ColumnValue::Float).ok()
+            }
+            (&ColumnValue::Numeric(_), &Value::Integer(value)) => {
+                Some(ColumnValue::Numeric(Numeric::from_i64(value)))
+            }
+            (&ColumnValue::Numeric(_), &Value::Float(value)) => {
+                Numeric::from_f64(value).map(ColumnValue::Numeric)
+            }
+            (&ColumnValue::Numeric(_), &Value::String(ref value)) => {
+                Numeric::parse(value).map(ColumnValue::Numeric)
+            }
+            (&ColumnValue::Boolean(_), &Value::Boolean(value)) => Some(ColumnValue::Boolean(value)),
+            (&ColumnValue::Boolean(_), &Value::String(ref value)) => {
+                parse_boolean(value).map(ColumnValue::Boolean)
+            }
+            (&ColumnValue::Text(_), value) => Some(ColumnValue::Text(format!("{}", value))),
+            (&ColumnValue::Date(_), &Value::String(ref value)) => {
+                NaiveDate::parse_from_str(value, DATE_FORMAT)
+                    .map(ColumnValue::Date)
+                    .ok()
+            }
+            (&ColumnValue::Time(_), &Value::String(ref value)) => TIME_FORMATS
+                .iter()
+                .filter_map(|format| NaiveTime::parse_from_str(value, format).ok())
+                .next()
+                .map(ColumnValue::Time),
+            (&ColumnValue::Timestamp(_), &Value::String(ref value)) => parse_timestamp(value)
+                .map(ColumnValue::Timestamp),
+            (&ColumnValue::TimestampTz(_), &Value::String(ref value)) => {
+                parse_timestamptz(value).map(ColumnValue::TimestampTz)
+            }
+            (&ColumnValue::Interval(_), &Value::String(ref value)) => {
+                Interval::parse(value).map(ColumnValue::Interval)
+            }
+            (&ColumnValue::Uuid(_), &Value::String(ref value)) => {
+                Uuid::parse_str(value).map(ColumnValue::Uuid).ok()
+            }
+            (&ColumnValue::Json(_), &Value::String(ref value)) => Some(ColumnValue::Json(
+                serde_json::from_str(value).unwrap_or_else(|_| JsonValue::String(value.clone())),
+            )),
+            (&ColumnValue::Json(_), value) => serde_json::to_value(value).map(ColumnValue::Json).ok(),
+            _ => None,
+        };
+
+        result.ok_or_else(|| format!("can not convert {} to {}", value, self.type_name()))
+    }
+
+    fn type_name(&self) -> &str {
+        match *self {
+            ColumnValue::Null => "null",
+            ColumnValue::Integer(_) => "integer",
+            ColumnValue::Float(_) => "float",
+            ColumnValue::Numeric(_) => "numeric",
+            ColumnValue::Boolean(_) => "boolean",
+            ColumnValue::Text(_) => "text",
+            ColumnValue::Date(_) => "date",
+            ColumnValue::Time(_) => "time",
+            ColumnValue::Timestamp(_) => "timestamp",
+            ColumnValue::TimestampTz(_) => "timestamp with time zone",
+            ColumnValue::Interval(_) => "interval",
+            ColumnValue::Uuid(_) => "uuid",
+            ColumnValue::Json(_) => "json",
+        }
+    }
+}
+
+fn read_value<T, F>(row: &Row, name: &str, callback: F) -> Result<ColumnValue, String>
+where
+    T: FromSql,
+    F: FnOnce(T) -> ColumnValue,
+{
+    let value: Option<PgResult<Option<T>>> = row.get_opt(name);
+
+    match value {
+        None => Err(format!("Column {} does not exists", name)),
+        Some(Err(err)) => Err(format!("Failed to get {} value - {}", name, err)),
+        Some(Ok(None)) => Ok(ColumnValue::Null),
+        Some(Ok(Some(value))) => Ok(callback(value)),
+    }
+}
+
+fn parse_boolean(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "t" | "true" | "y" | "yes" | "on" | "1" => Some(true),
+        "f" | "false" | "n" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    TIMESTAMP_FORMATS
+        .iter()
+        .filter_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .next()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, DATE_FORMAT)
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .or_else(|| parse_relative_time(value, Local::now().naive_local()))
+}
+
+fn parse_timestamptz(value: &str) -> Option<DateTime<Utc>> {
+    TIMESTAMPTZ_FORMATS
+        .iter()
+        .filter_map(|format| DateTime::parse_from_str(value, format).ok())
+        .next()
+        .map(|value| value.with_timezone(&Utc))
+        .or_else(|| parse_relative_time(value, Utc::now()))
+        .or_else(|| parse_timestamp(value).map(|value| Utc.from_utc_datetime(&value)))
+}
+
+/// Parses `now`, `now - INTERVAL` or `now + INTERVAL` relative to the given moment.
+fn parse_relative_time<T>(value: &str, now: T) -> Option<T>
+where
+    T: Add<Duration, Output = T> + Sub<Duration, Output = T>,
+{
+    let value = value.trim().strip_prefix("now")?.trim_start();
+
+    if value.is_empty() {
+        return Some(now);
+    }
+
+    if let Some(interval) = value.strip_prefix('+') {
+        Some(now + Interval::parse(interval)?.to_duration())
+    } else if let Some(interval) = value.strip_prefix('-') {
+        Some(now - Interval::parse(interval)?.to_duration())
+    } else {
+        None
+    }
+}
+
+impl ToSql for ColumnValue {
+    fn to_sql(&self, ty: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<Error + Sync + Send>> {
+        match *self {
+            ColumnValue::Null => Ok(IsNull::Yes),
+            ColumnValue::Integer(value) => match *ty {
+                INT2 if value >= i64::from(i16::MIN) && value <= i64::from(i16::MAX) => {
+                    (value as i16).to_sql(ty, out)
+                }
+                INT4 if value >= i64::from(i32::MIN) && value <= i64::from(i32::MAX) => {
+                    (value as i32).to_sql(ty, out)
+                }
+                OID if value >= 0 && value <= i64::from(u32::MAX) => (value as u32).to_sql(ty, out),
+                INT2 | INT4 | OID => Err(format!("value {} out of range for type {}", value, ty.name()).into()),
+                FLOAT4 => (value as f32).to_sql(ty, out),
+                FLOAT8 => (value as f64).to_sql(ty, out),
+                NUMERIC => Numeric::from_i64(value).to_sql(ty, out),
+                _ => value.to_sql(ty, out),
+            },
+            ColumnValue::Float(value) => match *ty {
+                FLOAT4 => (value as f32).to_sql(ty, out),
+                NUMERIC => match Numeric::from_f64(value) {
+                    Some(value) => value.to_sql(ty, out),
+                    None => Err("infinite value can not be converted to numeric".into()),
+                },
+                _ => value.to_sql(ty, out),
+            },
+            ColumnValue::Numeric(ref value) => match *ty {
+                FLOAT4 => (value.to_f64() as f32).to_sql(ty, out),
+                FLOAT8 => value.to_f64().to_sql(ty, out),
+                _ => value.to_sql(ty, out),
+            },
+            ColumnValue::Boolean(ref value) => value.to_sql(ty, out),
+            ColumnValue::Text(ref value) => value.to_sql(ty, out),
+            ColumnValue::Date(ref value) => value.to_sql(ty, out),
+            ColumnValue::Time(ref value) => value.to_sql(ty, out),
+            ColumnValue::Timestamp(ref value) => value.to_sql(ty, out),
+            ColumnValue::TimestampTz(ref value) => value.to_sql(ty, out),
+            ColumnValue::Interval(ref value) => value.to_sql(ty, out),
+            ColumnValue::Uuid(ref value) => value.to_sql(ty, out),
+            ColumnValue::Json(ref value) => value.to_sql(ty, out),
+        }
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+impl PartialEq for ColumnValue {
+    fn eq(&self, other: &ColumnValue) -> bool {
+        match (self, other) {
+            (&ColumnValue::Null, &ColumnValue::Null) => true,
+            (&ColumnValue::Json(ref left), &ColumnValue::Json(ref right)) => left == right,
+            _ => self.partial_cmp(other) == Some(Ordering::Equal),
+        }
+    }
+}
+
+impl PartialOrd for ColumnValue {
+    fn partial_cmp(&self, other: &ColumnValue) -> Option<Ordering> {
+        match (self, other) {
+            (&ColumnValue::Integer(ref left), &ColumnValue::Integer(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Integer(left), &ColumnValue::Float(ref right)) => {
+                (left as f64).partial_cmp(right)
+            }
+            (&ColumnValue::Float(ref left), &ColumnValue::Integer(right)) => {
+                left.partial_cmp(&(right as f64))
+            }
+            (&ColumnValue::Float(ref left), &ColumnValue::Float(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Numeric(ref left), &ColumnValue::Numeric(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Numeric(ref left), &ColumnValue::Integer(right)) => {
+                left.partial_cmp(&Numeric::from_i64(right))
+            }
+            (&ColumnValue::Integer(left), &ColumnValue::Numeric(ref right)) => {
+                Numeric::from_i64(left).partial_cmp(right)
+            }
+            (&ColumnValue::Numeric(ref left), &ColumnValue::Float(ref right)) => {
+                left.to_f64().partial_cmp(right)
+            }
+            (&ColumnValue::Float(ref left), &ColumnValue::Numeric(ref right)) => {
+                left.partial_cmp(&right.to_f64())
+            }
+            (&ColumnValue::Boolean(ref left), &ColumnValue::Boolean(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Text(ref left), &ColumnValue::Text(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Date(ref left), &ColumnValue::Date(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Time(ref left), &ColumnValue::Time(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Timestamp(ref left), &ColumnValue::Timestamp(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::TimestampTz(ref left), &ColumnValue::TimestampTz(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Interval(ref left), &ColumnValue::Interval(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Uuid(ref left), &ColumnValue::Uuid(ref right)) => {
+                left.partial_cmp(right)
+            }
+            (&ColumnValue::Json(ref left), &ColumnValue::Json(ref right)) if left == right => {
+                Some(Ordering::Equal)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for ColumnValue {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            ColumnValue::Null => write!(f, "NULL"),
+            ColumnValue::Integer(ref value) => write!(f, "{}", value),
+            ColumnValue::Float(ref value) => write!(f, "{}", value),
+            ColumnValue::Numeric(ref value) => write!(f, "{}", value),
+            ColumnValue::Boolean(ref value) => write!(f, "{}", value),
+            ColumnValue::Text(ref value) => write!(f, "{}", value),
+            ColumnValue::Date(ref value) => write!(f, "{}", value),
+            ColumnValue::Time(ref value) => write!(f, "{}", value),
+            ColumnValue::Timestamp(ref value) => write!(f, "{}", value),
+            ColumnValue::TimestampTz(ref value) => write!(f, "{}", value),
+            ColumnValue::Interval(ref value) => write!(f, "{}", value),
+            ColumnValue::Uuid(ref value) => write!(f, "{}", value.hyphenated()),
+            ColumnValue::Json(ref value) => write!(f, "{}", value),
+        }
+    }
+}
+
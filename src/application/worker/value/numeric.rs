@@ -0,0 +1,396 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::ops::Add;
+
+use postgres::types::FromSql;
+use postgres::types::IsNull;
+use postgres::types::ToSql;
+use postgres::types::Type;
+use postgres::types::NUMERIC;
+
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+const NUMERIC_MAX_DIGIT: u16 = 9999;
+const NAN: &str = "NaN";
+
+/// Exact NUMERIC value in canonical text form: integer part without leading zeros,
+/// fractional part without trailing zeros and zero without sign.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Numeric(String);
+
+impl Numeric {
+    pub fn zero() -> Numeric {
+        Numeric("0".into())
+    }
+
+    pub fn parse(value: &str) -> Option<Numeric> {
+        let value = value.trim();
+
+        if value.eq_ignore_ascii_case(NAN) {
+            return Some(Numeric(NAN.into()));
+        }
+
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let mut parts = digits.splitn(2, '.');
+        let integer = parts.next().unwrap_or("");
+        let fraction = parts.next().unwrap_or("");
+        let is_digits = |text: &str| text.bytes().all(|digit| digit.is_ascii_digit());
+
+        if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+            return None;
+        }
+
+        Some(Numeric::from_parts(negative, integer, fraction))
+    }
+
+    pub fn from_i64(value: i64) -> Numeric {
+        Numeric::from_parts(value < 0, &value.unsigned_abs().to_string(), "")
+    }
+
+    pub fn from_f64(value: f64) -> Option<Numeric> {
+        if value.is_nan() {
+            Some(Numeric(NAN.into()))
+        } else if value.is_infinite() {
+            None
+        } else {
+            Numeric::parse(&format!("{}", value))
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0.parse().unwrap_or(f64::NAN)
+    }
+
+    fn from_parts(negative: bool, integer: &str, fraction: &str) -> Numeric {
+        let integer = match integer.trim_start_matches('0') {
+            "" => "0",
+            integer => integer,
+        };
+        let fraction = fraction.trim_end_matches('0');
+        let mut text = String::with_capacity(integer.len() + fraction.len() + 2);
+
+        if negative && (integer != "0" || !fraction.is_empty()) {
+            text.push('-');
+        }
+
+        text.push_str(integer);
+
+        if !fraction.is_empty() {
+            text.push('.');
+            text.push_str(fraction);
+        }
+
+        Numeric(text)
+    }
+
+    fn is_nan(&self) -> bool {
+        self.0 == NAN
+    }
+
+    fn parts(&self) -> (bool, &str, &str) {
+        let (negative, digits) = match self.0.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, self.0.as_str()),
+        };
+        let mut parts = digits.splitn(2, '.');
+
+        (negative, parts.next().unwrap_or("0"), parts.next().unwrap_or(""))
+    }
+}
+
+impl Ord for Numeric {
+    /// NaN is equal to itself and greater than any other value, like in PostgreSQL.
+    fn cmp(&self, other: &Numeric) -> Ordering {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+
+        let (negative, integer, fraction) = self.parts();
+        let (other_negative, other_integer, other_fraction) = other.parts();
+        let magnitude = integer
+            .len()
+            .cmp(&other_integer.len())
+            .then_with(|| integer.cmp(other_integer))
+            .then_with(|| fraction.cmp(other_fraction));
+
+        match (negative, other_negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+        }
+    }
+}
+
+impl Add for Numeric {
+    type Output = Numeric;
+
+    /// Exact decimal addition, NaN plus any value is NaN like in PostgreSQL.
+    fn add(self, other: Numeric) -> Numeric {
+        if self.is_nan() || other.is_nan() {
+            return Numeric(NAN.into());
+        }
+
+        let (negative, integer, fraction) = self.parts();
+        let (other_negative, other_integer, other_fraction) = other.parts();
+        let scale = fraction.len().max(other_fraction.len());
+        let width = integer.len().max(other_integer.len()) + 1;
+        let digits = |integer: &str, fraction: &str| {
+            format!("{:0>width$}{:0<scale$}", integer, fraction, width = width, scale = scale)
+                .into_bytes()
+        };
+        let left = digits(integer, fraction);
+        let right = digits(other_integer, other_fraction);
+        let (negative, digits) = if negative == other_negative {
+            (negative, add_digits(&left, &right))
+        } else if left >= right {
+            (negative, subtract_digits(&left, &right))
+        } else {
+            (other_negative, subtract_digits(&right, &left))
+        };
+        let digits = String::from_utf8(digits).unwrap();
+        let (integer, fraction) = digits.split_at(width);
+
+        Numeric::from_parts(negative, integer, fraction)
+    }
+}
+
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Numeric) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Numeric {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromSql for Numeric {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Numeric, Box<Error + Sync + Send>> {
+        if raw.len() < 8 {
+            return Err("invalid numeric header".into());
+        }
+
+        let n_digits = read_u16(raw, 0) as usize;
+        let weight = i32::from(read_u16(raw, 2) as i16);
+        let sign = read_u16(raw, 4);
+
+        match sign {
+            NUMERIC_POS | NUMERIC_NEG => {}
+            NUMERIC_NAN => return Ok(Numeric(NAN.into())),
+            NUMERIC_PINF | NUMERIC_NINF => {
+                return Err("infinite numeric values are not supported".into())
+            }
+            sign => return Err(format!("invalid numeric sign 0x{:04X}", sign).into()),
+        }
+
+        if raw.len() != 8 + 2 * n_digits {
+            return Err("invalid numeric length".into());
+        }
+
+        let mut integer = String::new();
+        let mut fraction = String::new();
+
+        if weight < -1 {
+            fraction.push_str(&"0000".repeat((-weight - 1) as usize));
+        }
+
+        for index in 0..n_digits {
+            let digit = read_u16(raw, 8 + 2 * index);
+
+            if digit > NUMERIC_MAX_DIGIT {
+                return Err("invalid numeric digit".into());
+            }
+
+            if weight - (index as i32) >= 0 {
+                integer.push_str(&format!("{:04}", digit));
+            } else {
+                fraction.push_str(&format!("{:04}", digit));
+            }
+        }
+
+        if weight >= n_digits as i32 {
+            integer.push_str(&"0000".repeat((weight + 1) as usize - n_digits));
+        }
+
+        Ok(Numeric::from_parts(sign == NUMERIC_NEG, &integer, &fraction))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == NUMERIC
+    }
+}
+
+impl ToSql for Numeric {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<Error + Sync + Send>> {
+        if self.is_nan() {
+            write_u16(out, 0);
+            write_u16(out, 0);
+            write_u16(out, NUMERIC_NAN);
+            write_u16(out, 0);
+
+            return Ok(IsNull::No);
+        }
+
+        let (negative, integer, fraction) = self.parts();
+        let integer = integer.trim_start_matches('0');
+        let integer_padding = (4 - integer.len() % 4) % 4;
+        let fraction_padding = (4 - fraction.len() % 4) % 4;
+        let digits = format!(
+            "{}{}{}{}",
+            "0".repeat(integer_padding),
+            integer,
+            fraction,
+            "0".repeat(fraction_padding)
+        );
+        let mut groups: Vec<u16> = digits
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| chunk.iter().fold(0, |acc, digit| acc * 10 + u16::from(digit - b'0')))
+            .collect();
+        let mut weight = ((integer_padding + integer.len()) / 4) as i32 - 1;
+
+        while groups.first() == Some(&0) {
+            groups.remove(0);
+            weight -= 1;
+        }
+
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+
+        if groups.is_empty() {
+            weight = 0;
+        }
+
+        write_u16(out, groups.len() as u16);
+        write_u16(out, weight as i16 as u16);
+        write_u16(out, if negative { NUMERIC_NEG } else { NUMERIC_POS });
+        write_u16(out, fraction.len() as u16);
+
+        for group in groups {
+            write_u16(out, group);
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == NUMERIC
+    }
+
+    to_sql_checked!();
+}
+
+/// Adds ASCII digit strings of the same length, the first digit must be zero to keep carry.
+fn add_digits(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = vec![b'0'; left.len()];
+    let mut carry = 0;
+
+    for index in (0..left.len()).rev() {
+        let digit = (left[index] - b'0') + (right[index] - b'0') + carry;
+
+        result[index] = b'0' + digit % 10;
+        carry = digit / 10;
+    }
+
+    result
+}
+
+/// Subtracts ASCII digit strings of the same length, `left` must not be less than `right`.
+fn subtract_digits(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut result = vec![b'0'; left.len()];
+    let mut borrow = 0;
+
+    for index in (0..left.len()).rev() {
+        let subtrahend = (right[index] - b'0') + borrow;
+        let mut digit = left[index] - b'0';
+
+        if digit < subtrahend {
+            digit += 10;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+
+        result[index] = b'0' + digit - subtrahend;
+    }
+
+    result
+}
+
+fn write_u16(out: &mut Vec<u8>, value: u16) {
+    out.push((value >> 8) as u8);
+    out.push(value as u8);
+}
+
+fn read_u16(raw: &[u8], offset: usize) -> u16 {
+    (u16::from(raw[offset]) << 8) | u16::from(raw[offset + 1])
+}
+
+#[cfg(test)]
+mod test {
+    use postgres::types::FromSql;
+    use postgres::types::ToSql;
+    use postgres::types::NUMERIC;
+
+    use super::Numeric;
+
+    #[test]
+    fn compare_exactly() {
+        let large = Numeric::parse("12345678901234567.89").unwrap();
+        let close = Numeric::parse("12345678901234567.88").unwrap();
+
+        assert_ne!(large, close);
+        assert!(close < large);
+        assert_eq!(Numeric::parse("-0.50").unwrap(), Numeric::parse("-.5").unwrap());
+        assert!(Numeric::parse("-10").unwrap() < Numeric::parse("-9.99").unwrap());
+        assert!(Numeric::parse("0.051").unwrap() < Numeric::parse("0.5").unwrap());
+        assert!(Numeric::parse("1e3").is_none());
+    }
+
+    #[test]
+    fn add_exactly() {
+        let parse = |value| Numeric::parse(value).unwrap();
+
+        assert_eq!(parse("0.3"), parse("0.1") + parse("0.2"));
+        assert_eq!(
+            parse("100000000000000000000.01"),
+            parse("99999999999999999999.99") + parse("0.02")
+        );
+        assert_eq!(parse("-0.25"), parse("0.5") + parse("-0.75"));
+        assert_eq!(parse("0"), parse("-1.5") + parse("1.5"));
+        assert_eq!(parse("NaN"), parse("NaN") + parse("1"));
+    }
+
+    #[test]
+    fn decode_binary_format() {
+        for text in &["12345678901234567.89", "-0.00012", "100000000", "0", "NaN"] {
+            let value = Numeric::parse(text).unwrap();
+            let mut raw = Vec::new();
+            value.to_sql(&NUMERIC, &mut raw).unwrap();
+
+            assert_eq!(value, Numeric::from_sql(&NUMERIC, &raw).unwrap());
+        }
+
+        let infinity = [0, 0, 0, 0, 0xD0, 0, 0, 0];
+        let unknown = [0, 0, 0, 0, 0x80, 0, 0, 0];
+
+        assert!(Numeric::from_sql(&NUMERIC, &infinity).is_err());
+        assert!(Numeric::from_sql(&NUMERIC, &unknown).is_err());
+    }
+}
@@ -1,24 +1,75 @@
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+use std::io::Error as IoError;
 use std::num::ParseIntError;
 
+use serde_yaml::Error as YamlError;
+
 pub type ConfigurationResult<T> = Result<T, ConfigurationError>;
 
 #[derive(Debug)]
 pub enum ConfigurationError {
+    ConfigIoError { message: String },
+    ConfigYamlError { message: String },
+    UnknownProfile { name: String },
+    ServiceIoError { message: String },
+    UnknownService { name: String },
+    WrongUrl { message: String },
+    WrongSslMode { mode: String },
+    EmptySslRootCert,
+    WrongTarget { message: String },
     EmptyHostname,
     EmptyPort,
     WrongPort,
-    EmptyDatabase,
     EmptyUsername,
-    EmptyPassword,
     EmptyNWorkers,
     WrongNWorkers,
+    WrongFormat,
+    WrongTimeout,
+    WrongMaxFailures,
     EmptySuites,
 }
 
 impl ConfigurationError {
+    pub fn config_io_error(error: IoError) -> ConfigurationError {
+        ConfigurationError::ConfigIoError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn config_yaml_error(error: YamlError) -> ConfigurationError {
+        ConfigurationError::ConfigYamlError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn unknown_profile(name: &str) -> ConfigurationError {
+        ConfigurationError::UnknownProfile { name: name.into() }
+    }
+
+    pub fn service_io_error(error: IoError) -> ConfigurationError {
+        ConfigurationError::ServiceIoError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn unknown_service(name: &str) -> ConfigurationError {
+        ConfigurationError::UnknownService { name: name.into() }
+    }
+
+    pub fn wrong_url(message: String) -> ConfigurationError {
+        ConfigurationError::WrongUrl { message }
+    }
+
+    pub fn wrong_ssl_mode(mode: &str) -> ConfigurationError {
+        ConfigurationError::WrongSslMode { mode: mode.into() }
+    }
+
+    pub fn wrong_target(message: String) -> ConfigurationError {
+        ConfigurationError::WrongTarget { message }
+    }
+
     pub fn wrong_port(_: ParseIntError) -> ConfigurationError {
         ConfigurationError::WrongPort
     }
@@ -26,19 +77,54 @@ impl ConfigurationError {
     pub fn wrong_n_workers(_: ParseIntError) -> ConfigurationError {
         ConfigurationError::WrongNWorkers
     }
+
+    pub fn wrong_max_failures(_: ParseIntError) -> ConfigurationError {
+        ConfigurationError::WrongMaxFailures
+    }
 }
 
 impl Display for ConfigurationError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match *self {
+            ConfigurationError::ConfigIoError { ref message } => {
+                write!(f, "Config file IO error - {}", message)
+            }
+            ConfigurationError::ConfigYamlError { ref message } => {
+                write!(f, "Config file YAML error - {}", message)
+            }
+            ConfigurationError::UnknownProfile { ref name } => {
+                write!(f, "Unknown profile - {}", name)
+            }
+            ConfigurationError::ServiceIoError { ref message } => {
+                write!(f, "Service file IO error - {}", message)
+            }
+            ConfigurationError::UnknownService { ref name } => {
+                write!(f, "Unknown service - {}", name)
+            }
+            ConfigurationError::WrongUrl { ref message } => {
+                write!(f, "Wrong connection URI - {}", message)
+            }
+            ConfigurationError::WrongSslMode { ref mode } => write!(
+                f,
+                "Wrong SSL mode - {}, expected one of disable, allow, prefer, require, \
+                 verify-ca, verify-full",
+                mode
+            ),
+            ConfigurationError::EmptySslRootCert => {
+                write!(f, "Empty SSL root certificate, verify-ca mode requires it")
+            }
+            ConfigurationError::WrongTarget { ref message } => {
+                write!(f, "Wrong target - {}", message)
+            }
             ConfigurationError::EmptyHostname => write!(f, "Empty host name"),
             ConfigurationError::EmptyPort => write!(f, "Empty port"),
             ConfigurationError::WrongPort => write!(f, "Wrong port"),
-            ConfigurationError::EmptyDatabase => write!(f, "Empty database"),
             ConfigurationError::EmptyUsername => write!(f, "Empty user name"),
-            ConfigurationError::EmptyPassword => write!(f, "Empty password"),
             ConfigurationError::EmptyNWorkers => write!(f, "Empty number of workers"),
             ConfigurationError::WrongNWorkers => write!(f, "Wrong number of workers"),
+            ConfigurationError::WrongFormat => write!(f, "Wrong output format"),
+            ConfigurationError::WrongTimeout => write!(f, "Wrong timeout"),
+            ConfigurationError::WrongMaxFailures => write!(f, "Wrong maximum number of failures"),
             ConfigurationError::EmptySuites => write!(f, "Empty suites"),
         }
     }
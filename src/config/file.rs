@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_yaml;
+
+use dto::TimeLimit;
+use dto::Variables;
+
+use super::ConfigurationError;
+use super::ConfigurationResult;
+
+const DEFAULT_CONFIG_FILE: &str = "database-test.yaml";
+const MAX_WORKERS: usize = 100;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Profile {
+    pub url: Option<String>,
+    pub targets: Option<Vec<String>>,
+    pub hostname: Option<String>,
+    pub port: Option<u16>,
+    pub database: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub ssl_mode: Option<String>,
+    pub ssl_root_cert: Option<String>,
+    pub ssl_cert: Option<String>,
+    pub ssl_key: Option<String>,
+    pub n_workers: Option<usize>,
+    pub recursive: Option<bool>,
+    pub extensions: Option<Vec<String>>,
+    pub filter: Option<String>,
+    pub exclude: Option<Vec<String>>,
+    pub tags: Option<String>,
+    #[serde(default = "BTreeMap::default")] pub variables: Variables,
+    pub timeout: Option<TimeLimit>,
+    pub fail_fast: Option<bool>,
+    pub max_failures: Option<usize>,
+    pub junit: Option<PathBuf>,
+    pub format: Option<String>,
+    pub text_mode: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(flatten)] defaults: Profile,
+    #[serde(default = "BTreeMap::default")] profiles: BTreeMap<String, Profile>,
+}
+
+impl Profile {
+    pub fn load(path: Option<&str>, name: Option<&str>) -> ConfigurationResult<Profile> {
+        let path = match path {
+            Some(path) => Some(PathBuf::from(path)),
+            None if Path::new(DEFAULT_CONFIG_FILE).is_file() => Some(DEFAULT_CONFIG_FILE.into()),
+            None => None,
+        };
+        let mut file = match path {
+            Some(ref path) => ConfigFile::read(path)?,
+            None => ConfigFile::default(),
+        };
+
+        let profile = match name {
+            Some(name) => match file.profiles.remove(name) {
+                Some(profile) => file.defaults.merge(profile),
+                None => return Err(ConfigurationError::unknown_profile(name)),
+            },
+            None => file.defaults,
+        };
+
+        profile.validate()?;
+
+        Ok(profile)
+    }
+
+    fn validate(&self) -> ConfigurationResult<()> {
+        match self.n_workers {
+            Some(n_workers) if n_workers == 0 || n_workers > MAX_WORKERS => {
+                Err(ConfigurationError::WrongNWorkers)
+            }
+            _ => match self.max_failures {
+                Some(0) => Err(ConfigurationError::WrongMaxFailures),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    fn merge(mut self, other: Profile) -> Profile {
+        self.variables.extend(other.variables);
+
+        Profile {
+            url: other.url.or(self.url),
+            targets: other.targets.or(self.targets),
+            hostname: other.hostname.or(self.hostname),
+            port: other.port.or(self.port),
+            database: other.database.or(self.database),
+            username: other.username.or(self.username),
+            password: other.password.or(self.password),
+            ssl_mode: other.ssl_mode.or(self.ssl_mode),
+            ssl_root_cert: other.ssl_root_cert.or(self.ssl_root_cert),
+            ssl_cert: other.ssl_cert.or(self.ssl_cert),
+            ssl_key: other.ssl_key.or(self.ssl_key),
+            n_workers: other.n_workers.or(self.n_workers),
+            recursive: other.recursive.or(self.recursive),
+            extensions: other.extensions.or(self.extensions),
+            filter: other.filter.or(self.filter),
+            exclude: other.exclude.or(self.exclude),
+            tags: other.tags.or(self.tags),
+            variables: self.variables,
+            timeout: other.timeout.or(self.timeout),
+            fail_fast: other.fail_fast.or(self.fail_fast),
+            max_failures: other.max_failures.or(self.max_failures),
+            junit: other.junit.or(self.junit),
+            format: other.format.or(self.format),
+            text_mode: other.text_mode.or(self.text_mode),
+        }
+    }
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> ConfigurationResult<ConfigFile> {
+        let reader = File::open(path).map_err(ConfigurationError::config_io_error)?;
+
+        serde_yaml::from_reader(reader).map_err(ConfigurationError::config_yaml_error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_yaml;
+
+    use super::ConfigFile;
+
+    #[test]
+    fn merge_profile_with_defaults() {
+        let mut file: ConfigFile = serde_yaml::from_str(
+            "
+hostname: localhost
+database: app
+variables: { schema: public, limit: 10 }
+profiles:
+  staging:
+    hostname: staging.local
+    n_workers: 8
+    variables: { limit: 100 }
+",
+        ).unwrap();
+        let staging = file.profiles.remove("staging").unwrap();
+        let profile = file.defaults.merge(staging);
+
+        assert_eq!(Some("staging.local".into()), profile.hostname);
+        assert_eq!(Some("app".into()), profile.database);
+        assert_eq!(Some(8), profile.n_workers);
+        assert_eq!(2, profile.variables.len());
+        assert_eq!("100", format!("{}", profile.variables["limit"]));
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::ConfigurationError;
+use super::ConfigurationResult;
+
+const SERVICE_FILE: &str = "pg_service.conf";
+const USER_SERVICE_FILE: &str = ".pg_service.conf";
+const PASSWORD_FILE: &str = ".pgpass";
+const LOCALHOST: &str = "localhost";
+const WILDCARD: &str = "*";
+
+/// Connection parameters defined by libpq environment variables and service file.
+#[derive(Debug, Default)]
+pub struct Environment {
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub database: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub ssl_mode: Option<String>,
+    pub ssl_root_cert: Option<String>,
+    pub ssl_cert: Option<String>,
+    pub ssl_key: Option<String>,
+}
+
+impl Environment {
+    pub fn load() -> ConfigurationResult<Environment> {
+        let mut service = match env::var("PGSERVICE") {
+            Ok(name) => find_service(&name)?,
+            Err(_) => BTreeMap::new(),
+        };
+
+        Ok(Environment {
+            host: var_or("PGHOST", service.remove("host")),
+            port: var_or("PGPORT", service.remove("port")),
+            database: var_or("PGDATABASE", service.remove("dbname")),
+            user: var_or("PGUSER", service.remove("user")).or_else(|| env::var("USER").ok()),
+            password: var_or("PGPASSWORD", service.remove("password")),
+            ssl_mode: var_or("PGSSLMODE", service.remove("sslmode")),
+            ssl_root_cert: var_or("PGSSLROOTCERT", service.remove("sslrootcert")),
+            ssl_cert: var_or("PGSSLCERT", service.remove("sslcert")),
+            ssl_key: var_or("PGSSLKEY", service.remove("sslkey")),
+        })
+    }
+}
+
+/// Looks up password in password file like libpq does, file with group or world
+/// access is ignored.
+pub fn find_password(host: &str, port: u16, database: &str, user: &str) -> Option<String> {
+    let path = env::var_os("PGPASSFILE")
+        .map(PathBuf::from)
+        .or_else(|| home_file(PASSWORD_FILE))?;
+
+    if !path.is_file() || !is_private(&path) {
+        return None;
+    }
+
+    let host = if host.starts_with('/') { LOCALHOST } else { host };
+    let port = port.to_string();
+    let content = read_file(&path).ok()?;
+
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(split_password_line)
+        .find(|fields| {
+            fields.len() == 5
+                && [host, port.as_str(), database, user]
+                    .iter()
+                    .zip(fields.iter())
+                    .all(|(value, field)| field == WILDCARD || field == value)
+        })
+        .map(|mut fields| fields.remove(4))
+}
+
+fn find_service(name: &str) -> ConfigurationResult<BTreeMap<String, String>> {
+    let mut paths = Vec::new();
+
+    match env::var_os("PGSERVICEFILE") {
+        Some(path) => paths.push(PathBuf::from(path)),
+        None => paths.extend(home_file(USER_SERVICE_FILE)),
+    }
+
+    if let Some(dir) = env::var_os("PGSYSCONFDIR") {
+        paths.push(Path::new(&dir).join(SERVICE_FILE));
+    }
+
+    for path in paths.iter().filter(|path| path.is_file()) {
+        let content = read_file(path).map_err(ConfigurationError::service_io_error)?;
+
+        if let Some(service) = parse_service(&content, name) {
+            return Ok(service);
+        }
+    }
+
+    Err(ConfigurationError::unknown_service(name))
+}
+
+fn parse_service(content: &str, name: &str) -> Option<BTreeMap<String, String>> {
+    let mut service: Option<BTreeMap<String, String>> = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let section = line
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'));
+
+        if let Some(section) = section {
+            if service.is_some() {
+                break;
+            }
+
+            if section.trim() == name {
+                service = Some(BTreeMap::new());
+            }
+        } else if let Some(ref mut service) = service {
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or_default().trim();
+            let value = parts.next().unwrap_or_default().trim();
+
+            service.insert(key.into(), value.into());
+        }
+    }
+
+    service
+}
+
+fn split_password_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => fields.last_mut().unwrap().extend(chars.next()),
+            ':' if fields.len() < 5 => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+
+    fields
+}
+
+fn var_or(name: &str, value: Option<String>) -> Option<String> {
+    env::var(name).ok().or(value)
+}
+
+fn home_file(name: &str) -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| Path::new(&home).join(name))
+}
+
+fn read_file(path: &Path) -> Result<String, ::std::io::Error> {
+    let mut content = String::new();
+
+    File::open(path)?.read_to_string(&mut content)?;
+
+    Ok(content)
+}
+
+#[cfg(unix)]
+fn is_private(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match path.metadata() {
+        Ok(metadata) if metadata.permissions().mode() & 0o077 == 0 => true,
+        Ok(_) => {
+            eprintln!(
+                "WARNING: password file \"{}\" has group or world access; \
+                 permissions should be u=rw (0600) or less",
+                path.display()
+            );
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_private(_: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_service;
+    use super::split_password_line;
+
+    #[test]
+    fn parse_service_and_password_files() {
+        let content = "
+# services
+[staging]
+host=staging.local
+dbname = app
+
+[nightly]
+host=nightly.local
+";
+        let service = parse_service(content, "staging").unwrap();
+        assert_eq!("staging.local", service["host"]);
+        assert_eq!("app", service["dbname"]);
+        assert!(!service.contains_key("port"));
+        assert!(parse_service(content, "unknown").is_none());
+
+        assert_eq!(
+            vec!["db.local", "*", "app", "tester", "pass:w\\rd"],
+            split_password_line("db.local:*:app:tester:pass\\:w\\\\rd")
+        );
+    }
+}
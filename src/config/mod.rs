@@ -2,20 +2,48 @@ use std::path::PathBuf;
 
 use clap::ArgMatches;
 
+use dto::TimeLimit;
+use dto::Value;
+use dto::Variables;
+
 mod error;
+mod file;
+mod libpq;
+mod target;
+mod uri;
 
 pub use self::error::ConfigurationError;
 pub use self::error::ConfigurationResult;
+pub use self::target::Target;
+
+use self::file::Profile;
+use self::libpq::Environment;
 
+pub const CONFIG: &str = "CONFIG";
+pub const PROFILE: &str = "PROFILE";
+pub const URL: &str = "URL";
+pub const TARGET: &str = "TARGET";
 pub const HOSTNAME: &str = "HOSTNAME";
 pub const PORT: &str = "PORT";
 pub const DATABASE: &str = "DATABASE";
 pub const USERNAME: &str = "USERNAME";
 pub const PASSWORD: &str = "PASSWORD";
+pub const SSLMODE: &str = "SSLMODE";
+pub const SSLROOTCERT: &str = "SSLROOTCERT";
+pub const SSLCERT: &str = "SSLCERT";
+pub const SSLKEY: &str = "SSLKEY";
 pub const NWORKERS: &str = "NWORKERS";
 pub const RECURSIVE: &str = "RECURSIVE";
 pub const EXTENSIONS: &str = "EXTENSIONS";
 pub const FILTER: &str = "FILTER";
+pub const EXCLUDE: &str = "EXCLUDE";
+pub const TAGS: &str = "TAGS";
+pub const VARIABLES: &str = "VARIABLES";
+pub const JUNIT: &str = "JUNIT";
+pub const FORMAT: &str = "FORMAT";
+pub const TIMEOUT: &str = "TIMEOUT";
+pub const FAILFAST: &str = "FAILFAST";
+pub const MAXFAILURES: &str = "MAXFAILURES";
 pub const TEXTMODE: &str = "TEXTMODE";
 pub const BEVERBOSE: &str = "BEVERBOSE";
 pub const BEQUIET: &str = "BEQUIET";
@@ -23,57 +51,126 @@ pub const SUITES: &str = "SUITES";
 
 const DEFAULT_VERBOSITY: isize = 4;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    JsonLines,
+    Tap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SslMode {
+    Disable,
+    Allow,
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
 #[derive(Debug)]
 pub struct Configuration {
-    hostname: String,
-    port: u16,
-    database: String,
-    username: String,
-    password: String,
+    targets: Vec<Target>,
     n_workers: usize,
     recursive: bool,
     extensions: Option<Vec<String>>,
     filter: Option<String>,
+    exclude: Vec<String>,
+    tags: Option<String>,
+    variables: Variables,
+    timeout: Option<TimeLimit>,
+    max_failures: Option<usize>,
+    junit: Option<PathBuf>,
+    format: OutputFormat,
     text_mode: bool,
     verbosity: isize,
     suites: Vec<PathBuf>,
 }
 
+impl SslMode {
+    pub fn parse(value: &str) -> Option<SslMode> {
+        match value {
+            "disable" => Some(SslMode::Disable),
+            "allow" => Some(SslMode::Allow),
+            "prefer" => Some(SslMode::Prefer),
+            "require" => Some(SslMode::Require),
+            "verify-ca" => Some(SslMode::VerifyCa),
+            "verify-full" => Some(SslMode::VerifyFull),
+            _ => None,
+        }
+    }
+}
+
 impl Configuration {
     pub fn from_matches(matches: ArgMatches) -> ConfigurationResult<Configuration> {
+        let profile = Profile::load(matches.value_of(CONFIG), matches.value_of(PROFILE))?;
+        let environment = Environment::load()?;
+        let mut variables = profile.variables.clone();
+
+        if let Some(values) = matches.values_of(VARIABLES) {
+            variables.extend(values.map(parse_variable));
+        }
+
+        let targets = target::load_targets(&matches, &profile, &environment)?;
+
         Ok(Configuration {
-            hostname: matches
-                .value_of(HOSTNAME)
-                .ok_or(ConfigurationError::EmptyHostname)?
-                .into(),
-            port: matches
-                .value_of(PORT)
-                .ok_or(ConfigurationError::EmptyPort)?
-                .parse()
-                .map_err(ConfigurationError::wrong_port)?,
-            database: matches
-                .value_of(DATABASE)
-                .ok_or(ConfigurationError::EmptyDatabase)?
-                .into(),
-            username: matches
-                .value_of(USERNAME)
-                .ok_or(ConfigurationError::EmptyUsername)?
-                .into(),
-            password: matches
-                .value_of(PASSWORD)
-                .ok_or(ConfigurationError::EmptyPassword)?
-                .into(),
-            n_workers: matches
-                .value_of(NWORKERS)
-                .ok_or(ConfigurationError::EmptyNWorkers)?
-                .parse()
-                .map_err(ConfigurationError::wrong_n_workers)?,
-            recursive: matches.is_present(RECURSIVE),
-            extensions: matches
-                .values_of(EXTENSIONS)
-                .map(|values| values.map(|value| value.into()).collect()),
-            filter: matches.value_of(FILTER).map(|value| value.into()),
-            text_mode: matches.is_present(TEXTMODE),
+            targets,
+            n_workers: match profile.n_workers {
+                Some(n_workers) if matches.occurrences_of(NWORKERS) == 0 => n_workers,
+                _ => matches
+                    .value_of(NWORKERS)
+                    .ok_or(ConfigurationError::EmptyNWorkers)?
+                    .parse()
+                    .map_err(ConfigurationError::wrong_n_workers)?,
+            },
+            recursive: matches.is_present(RECURSIVE) || profile.recursive.unwrap_or(false),
+            extensions: match matches.values_of(EXTENSIONS) {
+                Some(values) => Some(values.map(|value| value.into()).collect()),
+                None => profile.extensions.clone(),
+            },
+            filter: matches
+                .value_of(FILTER)
+                .map(|value| value.into())
+                .or_else(|| profile.filter.clone()),
+            exclude: match matches.values_of(EXCLUDE) {
+                Some(values) => values.map(|value| value.into()).collect(),
+                None => profile.exclude.clone().unwrap_or_default(),
+            },
+            tags: matches
+                .value_of(TAGS)
+                .map(|value| value.into())
+                .or_else(|| profile.tags.clone()),
+            variables,
+            timeout: match matches.value_of(TIMEOUT) {
+                Some(value) => {
+                    Some(TimeLimit::parse(value).map_err(|_| ConfigurationError::WrongTimeout)?)
+                }
+                None => profile.timeout,
+            },
+            max_failures: match matches.value_of(MAXFAILURES) {
+                Some(value) => Some(
+                    value
+                        .parse()
+                        .map_err(ConfigurationError::wrong_max_failures)?,
+                ),
+                None if matches.is_present(FAILFAST) => Some(1),
+                None if profile.max_failures.is_some() => profile.max_failures,
+                None if profile.fail_fast.unwrap_or(false) => Some(1),
+                None => None,
+            },
+            junit: match matches.value_of(JUNIT) {
+                Some(value) => Some(value.into()),
+                None => profile.junit.clone(),
+            },
+            format: match explicit_value_of(&matches, FORMAT).or(profile.format.as_deref()) {
+                Some("text") | None => OutputFormat::Text,
+                Some("json") => OutputFormat::Json,
+                Some("jsonl") => OutputFormat::JsonLines,
+                Some("tap") => OutputFormat::Tap,
+                Some(_) => return Err(ConfigurationError::WrongFormat),
+            },
+            text_mode: matches.is_present(TEXTMODE) || profile.text_mode.unwrap_or(false),
             verbosity: DEFAULT_VERBOSITY + matches.occurrences_of(BEVERBOSE) as isize
                 - matches.occurrences_of(BEQUIET) as isize,
             suites: matches
@@ -84,24 +181,8 @@ impl Configuration {
         })
     }
 
-    pub fn hostname(&self) -> &String {
-        &self.hostname
-    }
-
-    pub fn port(&self) -> u16 {
-        self.port
-    }
-
-    pub fn database(&self) -> &String {
-        &self.database
-    }
-
-    pub fn username(&self) -> &String {
-        &self.username
-    }
-
-    pub fn password(&self) -> &String {
-        &self.password
+    pub fn targets(&self) -> &Vec<Target> {
+        &self.targets
     }
 
     pub fn n_workers(&self) -> usize {
@@ -120,6 +201,34 @@ impl Configuration {
         self.filter.as_ref()
     }
 
+    pub fn exclude(&self) -> &Vec<String> {
+        &self.exclude
+    }
+
+    pub fn tags(&self) -> Option<&String> {
+        self.tags.as_ref()
+    }
+
+    pub fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
+    pub fn timeout(&self) -> Option<TimeLimit> {
+        self.timeout
+    }
+
+    pub fn max_failures(&self) -> Option<usize> {
+        self.max_failures
+    }
+
+    pub fn junit(&self) -> Option<&PathBuf> {
+        self.junit.as_ref()
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
     pub fn text_mode(&self) -> bool {
         self.text_mode
     }
@@ -132,3 +241,19 @@ impl Configuration {
         &self.suites
     }
 }
+
+fn explicit_value_of<'a>(matches: &'a ArgMatches, name: &str) -> Option<&'a str> {
+    if matches.occurrences_of(name) > 0 {
+        matches.value_of(name)
+    } else {
+        None
+    }
+}
+
+fn parse_variable(variable: &str) -> (String, Value) {
+    let mut parts = variable.splitn(2, '=');
+    let name = parts.next().unwrap_or_default();
+    let value = parts.next().unwrap_or_default();
+
+    (name.into(), Value::String(value.into()))
+}
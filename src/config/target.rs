@@ -0,0 +1,254 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::ArgMatches;
+
+use super::explicit_value_of;
+use super::file::Profile;
+use super::libpq;
+use super::libpq::Environment;
+use super::uri::ConnectionUri;
+use super::ConfigurationError;
+use super::ConfigurationResult;
+use super::SslMode;
+use super::CONFIG;
+use super::DATABASE;
+use super::HOSTNAME;
+use super::PASSWORD;
+use super::PORT;
+use super::SSLCERT;
+use super::SSLKEY;
+use super::SSLMODE;
+use super::SSLROOTCERT;
+use super::TARGET;
+use super::URL;
+use super::USERNAME;
+
+/// Database connection parameters, named if several targets are given.
+#[derive(Debug)]
+pub struct Target {
+    name: Option<String>,
+    hostname: String,
+    port: u16,
+    database: String,
+    username: String,
+    password: Option<String>,
+    connect_timeout: Option<Duration>,
+    options: Vec<(String, String)>,
+    ssl_mode: SslMode,
+    ssl_root_cert: Option<PathBuf>,
+    ssl_cert: Option<PathBuf>,
+    ssl_key: Option<PathBuf>,
+}
+
+/// Creates targets from `--target` arguments or `targets` field of configuration file,
+/// every value is either `NAME=URI` or name of configuration file profile. Single
+/// unnamed target is created if no targets are given.
+pub fn load_targets(
+    matches: &ArgMatches,
+    profile: &Profile,
+    environment: &Environment,
+) -> ConfigurationResult<Vec<Target>> {
+    let specs: Vec<String> = match matches.values_of(TARGET) {
+        Some(values) => values.map(|value| value.into()).collect(),
+        None => profile.targets.clone().unwrap_or_default(),
+    };
+
+    if specs.is_empty() {
+        let uri = parse_uri(matches.value_of(URL).or(profile.url.as_deref()))?;
+
+        return Ok(vec![Target::new(None, matches, uri, profile, environment)?]);
+    }
+
+    let mut targets: Vec<Target> = Vec::with_capacity(specs.len());
+
+    for spec in &specs {
+        let target = match spec.find('=') {
+            Some(index) => {
+                let uri = parse_uri(Some(&spec[index + 1..]))?;
+
+                Target::new(Some(&spec[..index]), matches, uri, profile, environment)?
+            }
+            None => {
+                let profile = Profile::load(matches.value_of(CONFIG), Some(spec))?;
+                let uri = parse_uri(profile.url.as_deref())?;
+
+                Target::new(Some(spec), matches, uri, &profile, environment)?
+            }
+        };
+
+        if let Some(name) = target.name() {
+            if targets.iter().any(|item| item.name() == Some(name)) {
+                return Err(ConfigurationError::wrong_target(format!(
+                    "duplicate target name '{}'",
+                    name
+                )));
+            }
+        }
+
+        targets.push(target);
+    }
+
+    Ok(targets)
+}
+
+impl Target {
+    fn new(
+        name: Option<&str>,
+        matches: &ArgMatches,
+        uri: ConnectionUri,
+        profile: &Profile,
+        environment: &Environment,
+    ) -> ConfigurationResult<Target> {
+        if let Some(name) = name {
+            if name.trim().is_empty() || name.contains("::") {
+                return Err(ConfigurationError::wrong_target(format!(
+                    "invalid target name '{}'",
+                    name
+                )));
+            }
+        }
+
+        let hostname: String = explicit_value_of(matches, HOSTNAME)
+            .or(uri.host.as_deref())
+            .or(profile.hostname.as_deref())
+            .or(environment.host.as_deref())
+            .or_else(|| matches.value_of(HOSTNAME))
+            .ok_or(ConfigurationError::EmptyHostname)?
+            .into();
+        let port: u16 = match uri.port.or(profile.port) {
+            Some(port) if matches.occurrences_of(PORT) == 0 => port,
+            _ => explicit_value_of(matches, PORT)
+                .or(environment.port.as_deref())
+                .or_else(|| matches.value_of(PORT))
+                .ok_or(ConfigurationError::EmptyPort)?
+                .parse()
+                .map_err(ConfigurationError::wrong_port)?,
+        };
+        let username: String = explicit_value_of(matches, USERNAME)
+            .or(uri.user.as_deref())
+            .or(profile.username.as_deref())
+            .or(environment.user.as_deref())
+            .ok_or(ConfigurationError::EmptyUsername)?
+            .into();
+        let database: String = explicit_value_of(matches, DATABASE)
+            .or(uri.database.as_deref())
+            .or(profile.database.as_deref())
+            .or(environment.database.as_deref())
+            .unwrap_or(&username)
+            .into();
+        let password = explicit_value_of(matches, PASSWORD)
+            .or(uri.password.as_deref())
+            .or(profile.password.as_deref())
+            .or(environment.password.as_deref())
+            .map(|value| value.into())
+            .or_else(|| libpq::find_password(&hostname, port, &database, &username));
+        let ssl_mode = match explicit_value_of(matches, SSLMODE)
+            .or(uri.ssl_mode.as_deref())
+            .or(profile.ssl_mode.as_deref())
+            .or(environment.ssl_mode.as_deref())
+        {
+            Some(value) => {
+                SslMode::parse(value).ok_or_else(|| ConfigurationError::wrong_ssl_mode(value))?
+            }
+            None => SslMode::Disable,
+        };
+        let ssl_root_cert = matches
+            .value_of(SSLROOTCERT)
+            .or(uri.ssl_root_cert.as_deref())
+            .or(profile.ssl_root_cert.as_deref())
+            .or(environment.ssl_root_cert.as_deref())
+            .map(PathBuf::from);
+
+        if ssl_mode == SslMode::VerifyCa && ssl_root_cert.is_none() {
+            return Err(ConfigurationError::EmptySslRootCert);
+        }
+
+        let ssl_cert = matches
+            .value_of(SSLCERT)
+            .or(uri.ssl_cert.as_deref())
+            .or(profile.ssl_cert.as_deref())
+            .or(environment.ssl_cert.as_deref())
+            .map(PathBuf::from);
+        let ssl_key = matches
+            .value_of(SSLKEY)
+            .or(uri.ssl_key.as_deref())
+            .or(profile.ssl_key.as_deref())
+            .or(environment.ssl_key.as_deref())
+            .map(PathBuf::from);
+
+        Ok(Target {
+            name: name.map(|name| name.into()),
+            hostname,
+            port,
+            database,
+            username,
+            password,
+            connect_timeout: uri.connect_timeout,
+            options: uri.options,
+            ssl_mode,
+            ssl_root_cert,
+            ssl_cert,
+            ssl_key,
+        })
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn hostname(&self) -> &String {
+        &self.hostname
+    }
+
+    pub fn is_unix_socket(&self) -> bool {
+        self.hostname.starts_with('/')
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn database(&self) -> &String {
+        &self.database
+    }
+
+    pub fn username(&self) -> &String {
+        &self.username
+    }
+
+    pub fn password(&self) -> Option<&String> {
+        self.password.as_ref()
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    pub fn options(&self) -> &Vec<(String, String)> {
+        &self.options
+    }
+
+    pub fn ssl_mode(&self) -> SslMode {
+        self.ssl_mode
+    }
+
+    pub fn ssl_root_cert(&self) -> Option<&PathBuf> {
+        self.ssl_root_cert.as_ref()
+    }
+
+    pub fn ssl_cert(&self) -> Option<&PathBuf> {
+        self.ssl_cert.as_ref()
+    }
+
+    pub fn ssl_key(&self) -> Option<&PathBuf> {
+        self.ssl_key.as_ref()
+    }
+}
+
+fn parse_uri(url: Option<&str>) -> ConfigurationResult<ConnectionUri> {
+    match url {
+        Some(url) => ConnectionUri::parse(url).map_err(ConfigurationError::wrong_url),
+        None => Ok(ConnectionUri::default()),
+    }
+}
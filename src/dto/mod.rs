@@ -1,3 +1,17 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+use std::path::PathBuf;
+
+mod time_limit;
+mod variables;
+
+pub use self::time_limit::TimeLimit;
+
+pub type Variables = BTreeMap<String, Value>;
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub enum Condition {
     #[serde(rename = "=")] Equal,
@@ -8,22 +22,21 @@ pub enum Condition {
     #[serde(rename = ">=")] GreaterOrEqual,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum NullPolicy {
+    #[serde(rename = "allow")] Allow,
+    #[serde(rename = "deny")] Deny,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum Value {
     Integer(i64),
     Float(f64),
+    Boolean(bool),
     String(String),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(untagged)]
-pub enum Values {
-    Integer(Vec<i64>),
-    Float(Vec<f64>),
-    String(Vec<String>),
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum ColumnClause {
@@ -31,20 +44,46 @@ pub enum ColumnClause {
         name: String,
         condition: Condition,
         value: Value,
+        #[serde(default)] nulls: NullPolicy,
     },
     Range {
         name: String,
         from: Value,
         to: Value,
+        #[serde(default)] nulls: NullPolicy,
     },
     Any {
         name: String,
-        any: Values,
+        any: Vec<Value>,
+        #[serde(default)] nulls: NullPolicy,
     },
     Contains {
         name: String,
         contains: String,
+        #[serde(default)] nulls: NullPolicy,
     },
+    IsNull {
+        name: String,
+        is_null: bool,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum AggregateFunction {
+    #[serde(rename = "min")] Min,
+    #[serde(rename = "max")] Max,
+    #[serde(rename = "sum")] Sum,
+    #[serde(rename = "avg")] Avg,
+    #[serde(rename = "count_distinct")] CountDistinct,
+    #[serde(rename = "null_count")] NullCount,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AggregateClause {
+    function: AggregateFunction,
+    column: String,
+    condition: Condition,
+    value: Value,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -59,26 +98,158 @@ pub struct QueryClause {
     n_rows: NRowsClause,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Script {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Parameters {
+    List(Vec<Variables>),
+    Matrix(BTreeMap<String, Vec<Value>>),
+}
+
+pub type RowValues = Vec<(String, Option<Value>)>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ExpectedRow {
+    Map(BTreeMap<String, Option<Value>>),
+    Tuple(Vec<Option<Value>>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RowsClause {
+    #[serde(default)] ordered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] columns: Option<Vec<String>>,
+    rows: Vec<ExpectedRow>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ErrorClause {
+    #[serde(skip_serializing_if = "Option::is_none")] sqlstate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] message_contains: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] constraint: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompareClause {
+    with: String,
+    #[serde(default)] ordered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] columns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")] tolerance: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompareWithClause {
+    query: String,
+    #[serde(default)] condition: Condition,
+    #[serde(default)] ordered: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TestCase {
     name: String,
     description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::default")] tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")] skip: Option<QueryClause>,
+    #[serde(skip_serializing_if = "Option::is_none")] setup: Option<Script>,
+    #[serde(skip_serializing_if = "Option::is_none")] teardown: Option<Script>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default = "BTreeMap::default")]
+    variables: Variables,
+    #[serde(skip_serializing_if = "Option::is_none")] parameters: Option<Parameters>,
+    #[serde(skip)] parameter_values: Variables,
     query: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::default")] bind: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")] n_rows: Option<NRowsClause>,
     #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::default")]
     columns: Vec<ColumnClause>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::default")]
+    aggregates: Vec<AggregateClause>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::default")] unique: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] expect_rows: Option<RowsClause>,
+    #[serde(skip_serializing_if = "Option::is_none")] expect_error: Option<ErrorClause>,
+    #[serde(skip_serializing_if = "Option::is_none")] compare: Option<CompareClause>,
+    #[serde(skip_serializing_if = "Option::is_none")] compare_with: Option<CompareWithClause>,
+    #[serde(skip_serializing_if = "Option::is_none")] max_duration: Option<TimeLimit>,
+    #[serde(skip_serializing_if = "Option::is_none")] timeout: Option<TimeLimit>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TestSuite {
+    #[serde(skip)] path: Option<PathBuf>,
+    #[serde(skip)] target: Option<String>,
     name: String,
     description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::default")] tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")] skip: Option<QueryClause>,
+    #[serde(skip_serializing_if = "Option::is_none")] setup: Option<Script>,
+    #[serde(skip_serializing_if = "Option::is_none")] teardown: Option<Script>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default = "BTreeMap::default")]
+    variables: Variables,
+    #[serde(skip_serializing_if = "Option::is_none")] max_duration: Option<TimeLimit>,
+    #[serde(skip_serializing_if = "Option::is_none")] timeout: Option<TimeLimit>,
     #[serde(skip_serializing_if = "Vec::is_empty")] cases: Vec<TestCase>,
 }
 
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Value::Integer(ref value) => write!(f, "{}", value),
+            Value::Float(ref value) => write!(f, "{}", value),
+            Value::Boolean(ref value) => write!(f, "{}", value),
+            Value::String(ref value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Display for AggregateFunction {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::CountDistinct => "count_distinct",
+            AggregateFunction::NullCount => "null_count",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl Default for Condition {
+    fn default() -> Condition {
+        Condition::Equal
+    }
+}
+
+impl Default for NullPolicy {
+    fn default() -> NullPolicy {
+        NullPolicy::Deny
+    }
+}
+
 impl TestSuite {
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
+    pub fn set_path(&mut self, path: &Path) {
+        self.path = Some(path.into());
+    }
+
+    pub fn target(&self) -> Option<&String> {
+        self.target.as_ref()
+    }
+
+    pub fn set_target(&mut self, target: &str) {
+        self.target = Some(target.into());
+    }
+
     pub fn name(&self) -> &String {
         &self.name
     }
@@ -87,13 +258,84 @@ impl TestSuite {
         self.description.as_ref()
     }
 
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
     pub fn skip(&self) -> Option<&QueryClause> {
         self.skip.as_ref()
     }
 
+    pub fn setup(&self) -> Option<&Script> {
+        self.setup.as_ref()
+    }
+
+    pub fn teardown(&self) -> Option<&Script> {
+        self.teardown.as_ref()
+    }
+
+    pub fn max_duration(&self) -> Option<TimeLimit> {
+        self.max_duration
+    }
+
+    pub fn timeout(&self) -> Option<TimeLimit> {
+        self.timeout
+    }
+
+    pub fn set_default_timeout(&mut self, timeout: Option<TimeLimit>) {
+        if self.timeout.is_none() {
+            self.timeout = timeout;
+        }
+    }
+
     pub fn cases(&self) -> &Vec<TestCase> {
         &self.cases
     }
+
+    pub fn set_cases(&mut self, cases: Vec<TestCase>) {
+        self.cases = cases;
+    }
+
+    pub fn expand_parameters(&mut self) -> Result<(), String> {
+        let mut cases = Vec::with_capacity(self.cases.len());
+
+        for case in &self.cases {
+            let expanded = case.expand_parameters()
+                .map_err(|message| format!("case {} - {}", case.name, message))?;
+
+            cases.extend(expanded);
+        }
+
+        self.cases = cases;
+
+        Ok(())
+    }
+
+    pub fn resolve_variables(&mut self, overrides: &Variables) -> Result<(), String> {
+        let mut variables = self.variables.clone();
+        variables.extend(overrides.clone());
+
+        if let Some(ref mut skip) = self.skip {
+            skip.resolve_variables(&variables);
+        }
+
+        resolve_script(&mut self.setup, &variables);
+        resolve_script(&mut self.teardown, &variables);
+
+        for case in &mut self.cases {
+            let mut case_variables = self.variables.clone();
+            case_variables.extend(case.variables.clone());
+            case_variables.extend(overrides.clone());
+            case_variables.extend(case.parameter_values.clone());
+
+            case.resolve_variables(case_variables)
+                .map_err(|message| format!("case {} - {}", case.name, message))?;
+        }
+
+        self.variables = variables;
+
+        Ok(())
+    }
 }
 
 impl TestCase {
@@ -105,14 +347,34 @@ impl TestCase {
         self.description.as_ref()
     }
 
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
     pub fn skip(&self) -> Option<&QueryClause> {
         self.skip.as_ref()
     }
 
+    pub fn setup(&self) -> Option<&Script> {
+        self.setup.as_ref()
+    }
+
+    pub fn teardown(&self) -> Option<&Script> {
+        self.teardown.as_ref()
+    }
+
+    pub fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
     pub fn query(&self) -> &String {
         &self.query
     }
 
+    pub fn bind(&self) -> &Vec<String> {
+        &self.bind
+    }
+
     pub fn n_rows(&self) -> Option<&NRowsClause> {
         self.n_rows.as_ref()
     }
@@ -120,6 +382,210 @@ impl TestCase {
     pub fn columns(&self) -> &Vec<ColumnClause> {
         &self.columns
     }
+
+    pub fn aggregates(&self) -> &Vec<AggregateClause> {
+        &self.aggregates
+    }
+
+    pub fn unique(&self) -> &Vec<String> {
+        &self.unique
+    }
+
+    pub fn expect_rows(&self) -> Option<&RowsClause> {
+        self.expect_rows.as_ref()
+    }
+
+    pub fn expect_error(&self) -> Option<&ErrorClause> {
+        self.expect_error.as_ref()
+    }
+
+    pub fn compare(&self) -> Option<&CompareClause> {
+        self.compare.as_ref()
+    }
+
+    pub fn compare_with(&self) -> Option<&CompareWithClause> {
+        self.compare_with.as_ref()
+    }
+
+    pub fn max_duration(&self) -> Option<TimeLimit> {
+        self.max_duration
+    }
+
+    pub fn timeout(&self) -> Option<TimeLimit> {
+        self.timeout
+    }
+
+    fn expand_parameters(&self) -> Result<Vec<TestCase>, String> {
+        let parameter_sets = match self.parameters {
+            Some(ref parameters) => parameters.parameter_sets()?,
+            None => return Ok(vec![self.clone()]),
+        };
+        let has_placeholder = self.name.contains("${");
+        let mut result = Vec::with_capacity(parameter_sets.len());
+
+        for (index, parameter_set) in parameter_sets.into_iter().enumerate() {
+            let mut case = self.clone();
+            case.parameters = None;
+            case.parameter_values = parameter_set;
+
+            if !has_placeholder {
+                case.name = format!("{}[{}]", self.name, index + 1);
+            }
+
+            result.push(case);
+        }
+
+        Ok(result)
+    }
+
+    fn resolve_variables(&mut self, variables: Variables) -> Result<(), String> {
+        self.name = variables::substitute(&self.name, &variables);
+
+        if let Some(ref mut skip) = self.skip {
+            skip.resolve_variables(&variables);
+        }
+
+        resolve_script(&mut self.setup, &variables);
+        resolve_script(&mut self.teardown, &variables);
+
+        self.query = variables::substitute(&self.query, &variables);
+
+        if let Some(ref mut compare_with) = self.compare_with {
+            compare_with.query = variables::substitute(&compare_with.query, &variables);
+        }
+
+        for column in &mut self.columns {
+            column.resolve_variables(&variables);
+        }
+
+        for aggregate in &mut self.aggregates {
+            aggregate.value.resolve_variables(&variables);
+        }
+
+        for name in &self.bind {
+            if !variables.contains_key(name) {
+                return Err(format!("Undefined bind variable '{}'", name));
+            }
+        }
+
+        self.variables = variables;
+
+        Ok(())
+    }
+}
+
+impl Script {
+    pub fn statements(&self) -> Vec<String> {
+        match *self {
+            Script::Single(ref statement) => vec![statement.clone()],
+            Script::Multiple(ref statements) => statements.clone(),
+        }
+    }
+
+    fn resolve_variables(&mut self, variables: &Variables) {
+        match *self {
+            Script::Single(ref mut statement) => {
+                *statement = variables::substitute(statement, variables);
+            }
+            Script::Multiple(ref mut statements) => for statement in statements {
+                *statement = variables::substitute(statement, variables);
+            },
+        }
+    }
+}
+
+fn resolve_script(script: &mut Option<Script>, variables: &Variables) {
+    if let Some(ref mut script) = *script {
+        script.resolve_variables(variables);
+    }
+}
+
+impl Parameters {
+    fn parameter_sets(&self) -> Result<Vec<Variables>, String> {
+        let result = match *self {
+            Parameters::List(ref parameter_sets) => parameter_sets.clone(),
+            Parameters::Matrix(ref matrix) => {
+                let mut result = vec![Variables::new()];
+
+                for (name, values) in matrix {
+                    let mut product = Vec::with_capacity(result.len() * values.len());
+
+                    for parameter_set in &result {
+                        for value in values {
+                            let mut parameter_set = parameter_set.clone();
+                            parameter_set.insert(name.clone(), value.clone());
+                            product.push(parameter_set);
+                        }
+                    }
+
+                    result = product;
+                }
+
+                result
+            }
+        };
+
+        if result.is_empty() {
+            Err("Parameters must contain at least one parameter set".into())
+        } else {
+            Ok(result)
+        }
+    }
+}
+
+impl ColumnClause {
+    fn resolve_variables(&mut self, variables: &Variables) {
+        match *self {
+            ColumnClause::Compare { ref mut value, .. } => value.resolve_variables(variables),
+            ColumnClause::Range {
+                ref mut from,
+                ref mut to,
+                ..
+            } => {
+                from.resolve_variables(variables);
+                to.resolve_variables(variables);
+            }
+            ColumnClause::Any { ref mut any, .. } => for value in any {
+                value.resolve_variables(variables);
+            },
+            ColumnClause::Contains { ref mut contains, .. } => {
+                *contains = variables::substitute(contains, variables);
+            }
+            ColumnClause::IsNull { .. } => {}
+        }
+    }
+}
+
+impl AggregateClause {
+    pub fn function(&self) -> AggregateFunction {
+        self.function
+    }
+
+    pub fn column(&self) -> &String {
+        &self.column
+    }
+
+    pub fn condition(&self) -> Condition {
+        self.condition
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl Value {
+    fn resolve_variables(&mut self, variables: &Variables) {
+        let resolved = match *self {
+            Value::String(ref text) => match variables::placeholder_name(text) {
+                Some(name) if variables.contains_key(name) => variables[name].clone(),
+                _ => Value::String(variables::substitute(text, variables)),
+            },
+            _ => return,
+        };
+
+        *self = resolved;
+    }
 }
 
 impl QueryClause {
@@ -127,6 +593,10 @@ impl QueryClause {
         &self.query
     }
 
+    fn resolve_variables(&mut self, variables: &Variables) {
+        self.query = variables::substitute(&self.query, variables);
+    }
+
     pub fn n_rows(&self) -> &NRowsClause {
         &self.n_rows
     }
@@ -141,3 +611,156 @@ impl NRowsClause {
         self.value
     }
 }
+
+impl ErrorClause {
+    pub fn sqlstate(&self) -> Option<&String> {
+        self.sqlstate.as_ref()
+    }
+
+    pub fn message_contains(&self) -> Option<&String> {
+        self.message_contains.as_ref()
+    }
+
+    pub fn constraint(&self) -> Option<&String> {
+        self.constraint.as_ref()
+    }
+}
+
+impl CompareClause {
+    pub fn with(&self) -> &String {
+        &self.with
+    }
+
+    pub fn ordered(&self) -> bool {
+        self.ordered
+    }
+
+    pub fn columns(&self) -> Option<&Vec<String>> {
+        self.columns.as_ref()
+    }
+
+    pub fn tolerance(&self) -> Option<f64> {
+        self.tolerance
+    }
+}
+
+impl CompareWithClause {
+    pub fn query(&self) -> &String {
+        &self.query
+    }
+
+    pub fn condition(&self) -> Condition {
+        self.condition
+    }
+
+    pub fn ordered(&self) -> bool {
+        self.ordered
+    }
+}
+
+impl RowsClause {
+    pub fn ordered(&self) -> bool {
+        self.ordered
+    }
+
+    pub fn rows(&self) -> Result<Vec<RowValues>, String> {
+        let mut result = Vec::with_capacity(self.rows.len());
+
+        for (index, row) in self.rows.iter().enumerate() {
+            match (row, self.columns.as_ref()) {
+                (&ExpectedRow::Map(ref values), _) => result.push(
+                    values
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect(),
+                ),
+                (&ExpectedRow::Tuple(ref values), Some(columns)) if values.len() == columns.len() => {
+                    result.push(columns.iter().cloned().zip(values.iter().cloned()).collect())
+                }
+                (&ExpectedRow::Tuple(..), Some(..)) => {
+                    return Err(format!(
+                        "Expected row {} length does not match number of columns",
+                        index + 1
+                    ))
+                }
+                (&ExpectedRow::Tuple(..), None) => {
+                    return Err(format!("Expected row {} requires columns", index + 1))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_yaml;
+
+    use super::TestSuite;
+    use super::Value;
+    use super::Variables;
+
+    #[test]
+    fn expand_parameter_matrix() {
+        let mut suite: TestSuite = serde_yaml::from_str(
+            "
+name: suite
+cases:
+  - name: ${table} above ${limit}
+    parameters:
+      table: [users, groups]
+      limit: [1, 2]
+    query: select * from ${table}
+    columns:
+      - { name: id, condition: '>', value: '${limit}' }
+  - name: plain
+    parameters:
+      - { limit: 1 }
+    query: select ${limit}
+",
+        ).unwrap();
+
+        suite.expand_parameters().unwrap();
+        suite.resolve_variables(&Default::default()).unwrap();
+
+        let names: Vec<&String> = suite.cases().iter().map(|case| case.name()).collect();
+
+        assert_eq!(
+            vec![
+                "users above 1",
+                "groups above 1",
+                "users above 2",
+                "groups above 2",
+                "plain[1]",
+            ],
+            names
+        );
+        assert_eq!("select * from groups", suite.cases()[1].query());
+        assert_eq!("select 1", suite.cases()[4].query());
+    }
+
+    #[test]
+    fn parameters_are_not_overridden() {
+        let mut suite: TestSuite = serde_yaml::from_str(
+            "
+name: suite
+variables: { table: users }
+cases:
+  - name: by_id
+    parameters:
+      id: [1, 2]
+    query: select * from ${table} where id = ${id}
+",
+        ).unwrap();
+        let mut overrides = Variables::new();
+        overrides.insert("table".into(), Value::String("groups".into()));
+        overrides.insert("id".into(), Value::String("3".into()));
+
+        suite.expand_parameters().unwrap();
+        suite.resolve_variables(&overrides).unwrap();
+
+        assert_eq!("select * from groups where id = 1", suite.cases()[0].query());
+        assert_eq!("select * from groups where id = 2", suite.cases()[1].query());
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use serde::de::Error as DeError;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+const MILLIS_PER_SECOND: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeLimit(Duration);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTimeLimit {
+    Milliseconds(u64),
+    Text(String),
+}
+
+impl TimeLimit {
+    pub fn parse(value: &str) -> Result<TimeLimit, String> {
+        let value = value.trim();
+        let split = value
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(value.len());
+        let (amount, unit) = value.split_at(split);
+        let amount: f64 = amount
+            .parse()
+            .map_err(|_| format!("Invalid duration '{}'", value))?;
+        let millis = match unit.trim() {
+            "" | "ms" => amount,
+            "s" | "sec" => amount * MILLIS_PER_SECOND,
+            "m" | "min" => amount * 60.0 * MILLIS_PER_SECOND,
+            "h" => amount * 3600.0 * MILLIS_PER_SECOND,
+            unit => return Err(format!("Invalid duration unit '{}'", unit)),
+        };
+
+        TimeLimit::positive(millis as u64)
+    }
+
+    /// Zero is rejected, because `statement_timeout = 0` disables the timeout in PostgreSQL.
+    fn positive(millis: u64) -> Result<TimeLimit, String> {
+        if millis == 0 {
+            return Err("Duration must be at least 1 ms".into());
+        }
+
+        Ok(TimeLimit::from_millis(millis))
+    }
+
+    pub fn from_millis(millis: u64) -> TimeLimit {
+        TimeLimit(Duration::from_millis(millis))
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+
+    pub fn millis(&self) -> u64 {
+        self.0.as_secs() * 1000 + u64::from(self.0.subsec_millis())
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeLimit {
+    fn deserialize<D>(deserializer: D) -> Result<TimeLimit, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match RawTimeLimit::deserialize(deserializer)? {
+            RawTimeLimit::Milliseconds(millis) => {
+                TimeLimit::positive(millis).map_err(D::Error::custom)
+            }
+            RawTimeLimit::Text(text) => TimeLimit::parse(&text).map_err(D::Error::custom),
+        }
+    }
+}
+
+impl Serialize for TimeLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.millis())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimeLimit;
+
+    #[test]
+    fn parse_with_units() {
+        assert_eq!(Ok(TimeLimit::from_millis(250)), TimeLimit::parse("250"));
+        assert_eq!(Ok(TimeLimit::from_millis(250)), TimeLimit::parse("250ms"));
+        assert_eq!(Ok(TimeLimit::from_millis(1500)), TimeLimit::parse("1.5s"));
+        assert_eq!(Ok(TimeLimit::from_millis(120_000)), TimeLimit::parse("2 min"));
+        assert!(TimeLimit::parse("2 days").is_err());
+        assert!(TimeLimit::parse("fast").is_err());
+        assert!(TimeLimit::parse("0").is_err());
+        assert!(TimeLimit::parse("0.5ms").is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use super::Value;
+
+const PLACEHOLDER_START: &str = "${";
+const PLACEHOLDER_END: &str = "}";
+const ESCAPE: char = '$';
+
+/// Replaces `${name}` placeholders with variable values, `$${` is replaced with `${`.
+/// Placeholders of undefined variables and unterminated ones are left as is.
+pub fn substitute(text: &str, variables: &BTreeMap<String, Value>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(PLACEHOLDER_START) {
+        if rest[..start].ends_with(ESCAPE) {
+            result.push_str(&rest[..start - 1]);
+            result.push_str(PLACEHOLDER_START);
+            rest = &rest[start + PLACEHOLDER_START.len()..];
+            continue;
+        }
+
+        let tail = &rest[start + PLACEHOLDER_START.len()..];
+        let end = match tail.find(PLACEHOLDER_END) {
+            Some(end) => end,
+            None => break,
+        };
+
+        result.push_str(&rest[..start]);
+
+        match variables.get(&tail[..end]) {
+            Some(value) => result.push_str(&format!("{}", value)),
+            None => result.push_str(&rest[start..start + PLACEHOLDER_START.len() + end + 1]),
+        }
+
+        rest = &tail[end + PLACEHOLDER_END.len()..];
+    }
+
+    result.push_str(rest);
+
+    result
+}
+
+pub fn placeholder_name(text: &str) -> Option<&str> {
+    if text.starts_with(PLACEHOLDER_START) && text.ends_with(PLACEHOLDER_END) {
+        let name = &text[PLACEHOLDER_START.len()..text.len() - PLACEHOLDER_END.len()];
+
+        if !name.contains(PLACEHOLDER_END) {
+            return Some(name);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use dto::Value;
+
+    use super::substitute;
+
+    #[test]
+    fn substitute_all_placeholders() {
+        let mut variables = BTreeMap::new();
+        variables.insert("schema".into(), Value::String("public".into()));
+        variables.insert("limit".into(), Value::Integer(10));
+
+        assert_eq!(
+            "select * from public.t limit 10",
+            substitute("select * from ${schema}.t limit ${limit}", &variables)
+        );
+        assert_eq!("select $1", substitute("select $1", &variables));
+    }
+
+    #[test]
+    fn substitute_keeps_undefined_and_escaped_placeholders() {
+        let mut variables = BTreeMap::new();
+        variables.insert("name".into(), Value::String("value".into()));
+
+        assert_eq!(
+            "select '${other}', '${name}', 'value'",
+            substitute("select '${other}', '$${name}', '${name}'", &variables)
+        );
+        assert_eq!(
+            "do $$ begin raise notice '${'; end $$",
+            substitute("do $$ begin raise notice '${'; end $$", &variables)
+        );
+        assert_eq!("évalue ü${name}", substitute("é${name} ü$${name}", &variables));
+    }
+}
@@ -1,10 +1,17 @@
 #[macro_use]
 extern crate serde_derive;
 
+extern crate chrono;
 extern crate clap;
+extern crate openssl;
+#[macro_use]
 extern crate postgres;
+extern crate regex;
+extern crate serde;
+extern crate serde_json;
 extern crate serde_yaml;
 extern crate term;
+extern crate uuid;
 
 use std::process;
 
@@ -17,22 +24,39 @@ mod dto;
 mod validate;
 
 use application::Application;
+use application::ApplicationError;
 use application::ApplicationResult;
 use application::ApplicationStatus;
 use config::BEQUIET;
 use config::BEVERBOSE;
+use config::CONFIG;
 use config::Configuration;
 use config::DATABASE;
+use config::EXCLUDE;
 use config::EXTENSIONS;
+use config::FAILFAST;
 use config::FILTER;
+use config::FORMAT;
 use config::HOSTNAME;
+use config::JUNIT;
+use config::MAXFAILURES;
 use config::NWORKERS;
 use config::PASSWORD;
 use config::PORT;
+use config::PROFILE;
 use config::RECURSIVE;
+use config::SSLCERT;
+use config::SSLKEY;
+use config::SSLMODE;
+use config::SSLROOTCERT;
 use config::SUITES;
+use config::TAGS;
+use config::TARGET;
 use config::TEXTMODE;
+use config::TIMEOUT;
+use config::URL;
 use config::USERNAME;
+use config::VARIABLES;
 
 fn main() {
     process::exit(match start_app() {
@@ -47,58 +71,130 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
         .version("0.1")
         .author("Anton Shabanov <snakesolid@ngs.ru>")
         .about("Executes simple test suites for PostgreSQL databases.")
+        .arg(
+            Arg::with_name(CONFIG)
+                .short("c")
+                .long("config")
+                .takes_value(true)
+                .value_name("PATH")
+                .validator(validate::is_exists)
+                .help("Configuration file, database-test.yaml is used if exists")
+                .display_order(1),
+        )
+        .arg(
+            Arg::with_name(PROFILE)
+                .long("profile")
+                .takes_value(true)
+                .value_name("PROFILE")
+                .help("Configuration file profile")
+                .display_order(2),
+        )
+        .arg(
+            Arg::with_name(URL)
+                .long("url")
+                .takes_value(true)
+                .value_name("URL")
+                .help("PostgreSQL connection URI, e.g. postgresql://user@host:port/database")
+                .display_order(3),
+        )
+        .arg(
+            Arg::with_name(TARGET)
+                .long("target")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("NAME=URL")
+                .conflicts_with(URL)
+                .help("Named connection target, URI or configuration file profile, can be repeated")
+                .display_order(4),
+        )
         .arg(
             Arg::with_name(HOSTNAME)
                 .short("h")
                 .long("host-name")
-                .required(true)
                 .takes_value(true)
                 .value_name("HOSTNAME")
                 .default_value("localhost")
-                .help("PostgreSQL host name or IP address")
-                .display_order(1),
+                .help("PostgreSQL host name, IP address or Unix socket directory")
+                .display_order(5),
         )
         .arg(
             Arg::with_name(PORT)
                 .short("p")
                 .long("port")
-                .required(true)
                 .takes_value(true)
                 .value_name("PORT")
                 .default_value("5432")
                 .validator(validate::is_port)
                 .help("PostgreSQL port")
-                .display_order(2),
+                .display_order(6),
         )
         .arg(
             Arg::with_name(USERNAME)
                 .short("u")
                 .long("user-name")
-                .required(true)
                 .takes_value(true)
                 .value_name("USERNAME")
                 .help("PostgreSQL user name")
-                .display_order(3),
+                .display_order(7),
         )
         .arg(
             Arg::with_name(PASSWORD)
                 .short("w")
                 .long("password")
-                .required(true)
                 .takes_value(true)
                 .value_name("PASSWORD")
                 .help("PostgreSQL password")
-                .display_order(4),
+                .display_order(8),
         )
         .arg(
             Arg::with_name(DATABASE)
                 .short("d")
                 .long("database")
-                .required(true)
                 .takes_value(true)
                 .value_name("DATABASE")
                 .help("PostgreSQL database")
-                .display_order(5),
+                .display_order(9),
+        )
+        .arg(
+            Arg::with_name(SSLMODE)
+                .long("sslmode")
+                .takes_value(true)
+                .value_name("MODE")
+                .possible_values(&[
+                    "disable",
+                    "allow",
+                    "prefer",
+                    "require",
+                    "verify-ca",
+                    "verify-full",
+                ])
+                .help("TLS mode [default: disable]")
+                .display_order(10),
+        )
+        .arg(
+            Arg::with_name(SSLROOTCERT)
+                .long("sslrootcert")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Certificate authorities file used to verify server certificate")
+                .display_order(11),
+        )
+        .arg(
+            Arg::with_name(SSLCERT)
+                .long("sslcert")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Client certificate file")
+                .display_order(12),
+        )
+        .arg(
+            Arg::with_name(SSLKEY)
+                .long("sslkey")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Client private key file")
+                .display_order(13),
         )
         .arg(
             Arg::with_name(NWORKERS)
@@ -109,14 +205,14 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
                 .default_value("4")
                 .validator(validate::is_n_workers)
                 .help("Number of worker threads")
-                .display_order(6),
+                .display_order(14),
         )
         .arg(
             Arg::with_name(RECURSIVE)
                 .short("r")
                 .long("recursive")
                 .help("Read all files under each directory, recursively")
-                .display_order(7),
+                .display_order(15),
         )
         .arg(
             Arg::with_name(EXTENSIONS)
@@ -126,7 +222,7 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
                 .multiple(true)
                 .value_name("EXTENSIONS")
                 .help("File extension filter for recursive search")
-                .display_order(8),
+                .display_order(16),
         )
         .arg(
             Arg::with_name(FILTER)
@@ -134,15 +230,89 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
                 .long("filter")
                 .takes_value(true)
                 .value_name("FILTER")
-                .help("Filter test suites by name")
-                .display_order(9),
+                .help("Filter test suites and cases by name, SUITE::CASE patterns are allowed")
+                .display_order(17),
+        )
+        .arg(
+            Arg::with_name(EXCLUDE)
+                .short("x")
+                .long("exclude")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("EXCLUDE")
+                .help("Exclude test suites and cases by name, can be applied several times")
+                .display_order(18),
+        )
+        .arg(
+            Arg::with_name(TAGS)
+                .long("tags")
+                .takes_value(true)
+                .value_name("EXPRESSION")
+                .help("Filter test cases by tag expression, e.g. \"smoke and not slow\"")
+                .display_order(19),
+        )
+        .arg(
+            Arg::with_name(VARIABLES)
+                .long("var")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("NAME=VALUE")
+                .validator(validate::is_variable)
+                .help("Set suite variable, can be applied several times")
+                .display_order(20),
+        )
+        .arg(
+            Arg::with_name(TIMEOUT)
+                .long("timeout")
+                .takes_value(true)
+                .value_name("TIMEOUT")
+                .validator(validate::is_time_limit)
+                .help("Default statement timeout for test cases")
+                .display_order(21),
+        )
+        .arg(
+            Arg::with_name(FAILFAST)
+                .long("fail-fast")
+                .help("Stop running test cases after the first failure")
+                .conflicts_with(MAXFAILURES)
+                .display_order(22),
+        )
+        .arg(
+            Arg::with_name(MAXFAILURES)
+                .long("max-failures")
+                .takes_value(true)
+                .value_name("N")
+                .validator(validate::is_max_failures)
+                .help("Stop running test cases after N failures")
+                .conflicts_with(FAILFAST)
+                .display_order(23),
+        )
+        .arg(
+            Arg::with_name(JUNIT)
+                .long("junit")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Write JUnit XML report to file")
+                .display_order(24),
+        )
+        .arg(
+            Arg::with_name(FORMAT)
+                .long("format")
+                .takes_value(true)
+                .value_name("FORMAT")
+                .possible_values(&["text", "json", "jsonl", "tap"])
+                .default_value("text")
+                .help("Output format")
+                .display_order(25),
         )
         .arg(
             Arg::with_name(TEXTMODE)
                 .short("t")
                 .long("text-mode")
                 .help("Use plain text mode instead of color")
-                .display_order(10),
+                .display_order(26),
         )
         .arg(
             Arg::with_name(BEVERBOSE)
@@ -151,7 +321,7 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
                 .multiple(true)
                 .help("Be verbose, can be applied several times")
                 .conflicts_with(BEQUIET)
-                .display_order(11),
+                .display_order(27),
         )
         .arg(
             Arg::with_name(BEQUIET)
@@ -160,7 +330,7 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
                 .multiple(true)
                 .help("Be quiet, can be applied several times")
                 .conflicts_with(BEVERBOSE)
-                .display_order(12),
+                .display_order(28),
         )
         .arg(
             Arg::with_name(SUITES)
@@ -173,14 +343,12 @@ fn start_app() -> ApplicationResult<ApplicationStatus> {
         )
         .get_matches();
 
-    let config = match Configuration::from_matches(matches) {
-        Ok(config) => config,
-        Err(err) => panic!("{}", err),
-    };
-    let result = Application::new(&config).and_then(Application::run);
+    let result = Configuration::from_matches(matches)
+        .map_err(ApplicationError::configuration_error)
+        .and_then(|config| Application::new(&config).and_then(Application::run));
 
     if let Err(ref err) = result {
-        println!("{}", err);
+        eprintln!("{}", err);
     }
 
     result
@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use dto::TimeLimit;
+
 pub fn is_port(val: String) -> Result<(), String> {
     match val.parse::<u16>() {
         Ok(..) => Ok(()),
@@ -20,6 +22,16 @@ pub fn is_n_workers(val: String) -> Result<(), String> {
     }
 }
 
+pub fn is_max_failures(val: String) -> Result<(), String> {
+    match val.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(()),
+        Ok(..) | Err(..) => Err(format!(
+            "Maximum number of failures must be positive number, but {} given",
+            val
+        )),
+    }
+}
+
 pub fn is_exists(val: String) -> Result<(), String> {
     let path = Path::new(&val);
 
@@ -29,3 +41,23 @@ pub fn is_exists(val: String) -> Result<(), String> {
         Err(format!("Test suite {} must exists", val))
     }
 }
+
+pub fn is_variable(val: String) -> Result<(), String> {
+    match val.find('=') {
+        Some(index) if index > 0 => Ok(()),
+        Some(..) | None => Err(format!(
+            "Variable must be in format NAME=VALUE, but {} given",
+            val
+        )),
+    }
+}
+
+pub fn is_time_limit(val: String) -> Result<(), String> {
+    match TimeLimit::parse(&val) {
+        Ok(..) => Ok(()),
+        Err(message) => Err(format!(
+            "Timeout must be number of milliseconds or duration with unit, but {} given - {}",
+            val, message
+        )),
+    }
+}